@@ -10,7 +10,7 @@ fn main() -> Result<(), Mpu6050Error<LinuxI2CError>> {
     let mut mpu = Mpu6050::new(i2c);
 
     mpu.init(&mut delay)?;
-    mpu.setup_motion_detection()?;
+    mpu.setup_motion_detection_default()?;
 
     let mut count: u8 = 0;
 
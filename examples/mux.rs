@@ -0,0 +1,28 @@
+use i2cdev::linux::LinuxI2CError;
+use linux_embedded_hal::{Delay, I2cdev};
+use mpu6050::mux::{MuxChannel, TCA9548A_ADDR};
+use mpu6050::*;
+
+fn main() -> Result<(), Mpu6050Error<LinuxI2CError>> {
+    let i2c = I2cdev::new("/dev/i2c-1").map_err(Mpu6050Error::I2c)?;
+    let bus = shared_bus::BusManagerSimple::new(i2c);
+    let mut delay = Delay;
+
+    // four sensors at the default address, on channels 0 - 3 of a TCA9548A
+    let mut mpus = Vec::new();
+    for channel in 0..4 {
+        let i2c = MuxChannel::new(bus.acquire_i2c(), TCA9548A_ADDR, channel)?;
+        mpus.push(Mpu6050::new(i2c));
+    }
+
+    for mpu in mpus.iter_mut() {
+        mpu.init(&mut delay)?;
+    }
+
+    loop {
+        for (channel, mpu) in mpus.iter_mut().enumerate() {
+            let acc = mpu.get_acc()?;
+            println!("channel {}: acc: {:?}", channel, acc);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use embedded_hal::blocking::i2c::WriteRead;
+use i2cdev::linux::LinuxI2CError;
+use linux_embedded_hal::{Delay, I2cdev};
+use mpu6050::*;
+
+// address of a second device on the same bus, e.g. a magnetometer
+const OTHER_ADDR: u8 = 0x0d;
+
+fn main() -> Result<(), Mpu6050Error<LinuxI2CError>> {
+    let i2c = I2cdev::new("/dev/i2c-1").map_err(Mpu6050Error::I2c)?;
+
+    // the bus manager hands out one proxy per driver
+    let bus = shared_bus::BusManagerSimple::new(i2c);
+
+    let mut delay = Delay;
+    let mut mpu = Mpu6050::new(bus.acquire_i2c());
+    let mut other = bus.acquire_i2c();
+
+    mpu.init(&mut delay)?;
+
+    loop {
+        // get accelerometer data, scaled with sensitivity
+        let acc = mpu.get_acc()?;
+        println!("acc: {:?}", acc);
+
+        // talk to the other device through its own proxy
+        let mut status = [0u8; 1];
+        other
+            .write_read(OTHER_ADDR, &[0x06], &mut status)
+            .map_err(Mpu6050Error::I2c)?;
+        println!("other: {:#04x}", status[0]);
+    }
+}
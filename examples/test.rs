@@ -41,21 +41,46 @@ fn main() -> Result<(), Mpu6050Error<LinuxI2CError>> {
     mpu.set_accel_hpf(ACCEL_HPF::_HOLD)?;
     assert_eq!(mpu.get_accel_hpf()?, ACCEL_HPF::_HOLD);
 
+    // dlpf: per default disabled, see CONFIG
+    println!("Test dlpf");
+    assert_eq!(mpu.get_dlpf()?, DLPF::DISABLED);
+    mpu.set_dlpf(DLPF::_44)?;
+    assert_eq!(mpu.get_dlpf()?, DLPF::_44);
+    mpu.set_dlpf(DLPF::_5)?;
+    assert_eq!(mpu.get_dlpf()?, DLPF::_5);
+
+    // sample rate divider: per default 0
+    println!("Test sample rate");
+    assert_eq!(mpu.get_sample_rate_divider()?, 0);
+    mpu.set_sample_rate_divider(7)?;
+    assert_eq!(mpu.get_sample_rate_divider()?, 7);
+    // DLPF is enabled above -> 1 kHz gyro output rate
+    mpu.set_sample_rate(100)?;
+    assert_eq!(mpu.get_sample_rate_divider()?, 9);
+
     // test sleep. Default no, in wake()
     println!("Test sleep");
-    assert_eq!(mpu.get_sleep_enabled()?, false);
+    assert!(!mpu.get_sleep_enabled()?);
     mpu.set_sleep_enabled(true)?;
-    assert_eq!(mpu.get_sleep_enabled()?, true);
+    assert!(mpu.get_sleep_enabled()?);
     mpu.set_sleep_enabled(false)?;
-    assert_eq!(mpu.get_sleep_enabled()?, false);
+    assert!(!mpu.get_sleep_enabled()?);
+
+    // test standby: all axes enabled per default
+    println!("Test standby");
+    assert_eq!(mpu.get_standby()?, ([false; 3], [false; 3]));
+    mpu.set_standby([false; 3], [false, false, true])?;
+    assert_eq!(mpu.get_standby()?, ([false; 3], [false, false, true]));
+    mpu.set_standby([false; 3], [false; 3])?;
+    assert_eq!(mpu.get_standby()?, ([false; 3], [false; 3]));
 
     // test temp enable/disable
     println!("Test temp enable/disable");
     mpu.set_temp_enabled(false)?;
-    assert_eq!(mpu.get_temp_enabled()?, false);
+    assert!(!mpu.get_temp_enabled()?);
     assert_eq!(mpu.get_temp()?, 36.53);
     mpu.set_temp_enabled(true)?;
-    assert_eq!(mpu.get_temp_enabled()?, true);
+    assert!(mpu.get_temp_enabled()?);
     assert_ne!(mpu.get_temp()?, 36.53);
 
     // Test clksel: GXAXIS per default, set in wake()
@@ -80,10 +105,11 @@ fn main() -> Result<(), Mpu6050Error<LinuxI2CError>> {
     println!("Test reset");
     mpu.reset_device(&mut delay)?;
     assert_eq!(mpu.get_accel_hpf()?, ACCEL_HPF::_RESET);
+    assert_eq!(mpu.get_dlpf()?, DLPF::DISABLED);
     assert_eq!(mpu.get_accel_range()?, AccelRange::G2);
     assert_eq!(mpu.get_gyro_range()?, GyroRange::D250);
-    assert_eq!(mpu.get_sleep_enabled()?, true);
-    assert_eq!(mpu.get_temp_enabled()?, true);
+    assert!(mpu.get_sleep_enabled()?);
+    assert!(mpu.get_temp_enabled()?);
 
     println!("Test successful");
     Ok(())
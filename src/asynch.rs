@@ -0,0 +1,523 @@
+//! Async version of the driver, based on `embedded_hal_async`. Enabled with the `async` feature.
+//!
+//! Mirrors the core of the blocking [`Mpu6050`](crate::Mpu6050) driver: init, reading accel, gyro and temperature,
+//! and gyro calibration. Conversion state and math (ranges, fine tune offsets, temperature calibration and
+//! the gyro temperature model) and calibration math are shared with the blocking driver.
+//!
+//! ```ignore
+//! let mut mpu = Mpu6050Async::new(i2c);
+//! mpu.init(&mut delay).await?;
+//! let acc = mpu.get_acc().await?;
+//! ```
+
+use crate::device::*;
+use crate::{
+    bits, calibration_converged, calibration_mean, calibration_residual, gyro_calibration_offsets,
+    read_vec_i16, read_word_2c, Acceleration, AngularVelocity, Conversion, GyroTempModel,
+    Mpu6050Error, CALIBRATION_DISCARD_COUNT, CALIBRATION_MAX_STEPS, CALIBRATION_MEASUREMENT_COUNT,
+    CALIBRATION_SAMPLE_DELAY_MS, GYRO_CALIBRATION_TARGET_MEAN,
+};
+#[cfg(feature = "defmt")]
+use defmt::info;
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+#[allow(unused_imports)]
+use micromath::{vector::Vector3d, F32Ext};
+
+/// Handles all async operations on/with Mpu6050
+pub struct Mpu6050Async<I> {
+    i2c: I,
+    slave_addr: u8,
+    conv: Conversion,
+    model: Option<DeviceModel>,
+}
+
+impl<I, E> Mpu6050Async<I>
+where
+    I: I2c<Error = E>,
+{
+    /// Side effect free constructor with default sensitivies, no calibration
+    pub fn new(i2c: I) -> Self {
+        Mpu6050Async::new_with_addr(i2c, DEFAULT_SLAVE_ADDR)
+    }
+
+    /// custom sensitivity, the ranges are written by `init`
+    pub fn new_with_sens(i2c: I, arange: AccelRange, grange: GyroRange) -> Self {
+        Mpu6050Async::new_with_addr_and_sens(i2c, DEFAULT_SLAVE_ADDR, arange, grange)
+    }
+
+    /// Same as `new`, but the chip address can be specified (e.g. 0x69, if the A0 pin is pulled up)
+    pub fn new_with_addr(i2c: I, slave_addr: u8) -> Self {
+        Mpu6050Async::new_with_addr_and_sens(i2c, slave_addr, AccelRange::G2, GyroRange::D250)
+    }
+
+    /// Combination of `new_with_sens` and `new_with_addr`. All other constructors end up here
+    pub fn new_with_addr_and_sens(
+        i2c: I,
+        slave_addr: u8,
+        arange: AccelRange,
+        grange: GyroRange,
+    ) -> Self {
+        Mpu6050Async {
+            i2c,
+            slave_addr,
+            conv: Conversion::new(arange, grange),
+            model: None,
+        }
+    }
+
+    /// Consumes the driver and returns the owned i2c bus, e.g. to hand it to another driver
+    pub fn release(self) -> I {
+        self.i2c
+    }
+
+    /// Mutable access to the i2c bus, e.g. for raw transactions on a shared bus
+    pub fn i2c_mut(&mut self) -> &mut I {
+        &mut self.i2c
+    }
+
+    /// Wakes MPU6050 with all sensors enabled (default)
+    async fn wake<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
+        // MPU6050 has sleep enabled by default -> set bit 0 to wake
+        // Set clock source to be PLL with x-axis gyroscope reference, bits 2:0 = 001 (See Register Map )
+        self.write_byte(PWR_MGMT_1::ADDR, 0x01).await?;
+        delay.delay_ms(100).await;
+        Ok(())
+    }
+
+    /// Init wakes MPU6050, verifies register addr, e.g. in i2c, and writes the ranges given to the
+    /// constructor. The accel HPF is left untouched
+    /// A bus error on the wake, the first access, returns `DeviceNotResponding`
+    pub async fn init<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
+        let addr = self.slave_addr;
+        self.wake(delay).await.map_err(|e| match e {
+            Mpu6050Error::I2c(_) => Mpu6050Error::DeviceNotResponding(addr),
+            e => e,
+        })?;
+        self.verify().await?;
+        // waking enables the temperature sensor
+        self.conv.temp_enabled = true;
+        self.set_accel_range(self.conv.acc_range).await?;
+        self.set_gyro_range(self.conv.gyro_range).await?;
+        Ok(())
+    }
+
+    /// Verifies device identity with the WHOAMI Register, independent of the slave address
+    async fn verify(&mut self) -> Result<(), Mpu6050Error<E>> {
+        let chip_id = self.who_am_i().await?;
+        match DeviceModel::from_chip_id(chip_id) {
+            Some(model) => {
+                self.model = Some(model);
+                Ok(())
+            }
+            None => Err(Mpu6050Error::InvalidChipId(chip_id)),
+        }
+    }
+
+    /// get raw content of the WHOAMI register
+    pub async fn who_am_i(&mut self) -> Result<u8, Mpu6050Error<E>> {
+        self.read_byte(WHOAMI).await
+    }
+
+    /// Device model detected by `init`, None before a successful init
+    pub fn get_device_model(&self) -> Option<DeviceModel> {
+        self.model
+    }
+
+    /// set accel high pass filter mode
+    pub async fn set_accel_hpf(&mut self, mode: ACCEL_HPF) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            ACCEL_CONFIG::ADDR,
+            ACCEL_CONFIG::ACCEL_HPF.bit,
+            ACCEL_CONFIG::ACCEL_HPF.length,
+            mode as u8,
+        )
+        .await
+    }
+
+    /// Set gyro range, and update sensitivity accordingly
+    pub async fn set_gyro_range(&mut self, range: GyroRange) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            GYRO_CONFIG::ADDR,
+            GYRO_CONFIG::FS_SEL.bit,
+            GYRO_CONFIG::FS_SEL.length,
+            range as u8,
+        )
+        .await?;
+
+        self.conv.gyro_sensitivity = range.sensitivity();
+        self.conv.gyro_range = range;
+        Ok(())
+    }
+
+    /// set accel range, and update sensitivy accordingly
+    pub async fn set_accel_range(&mut self, range: AccelRange) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            ACCEL_CONFIG::ADDR,
+            ACCEL_CONFIG::FS_SEL.bit,
+            ACCEL_CONFIG::FS_SEL.length,
+            range as u8,
+        )
+        .await?;
+
+        self.conv.acc_sensitivity = range.sensitivity();
+        self.conv.acc_range = range;
+        Ok(())
+    }
+
+    /// Reads rotation (gyro/acc) from specified register returning as Vector3s<i32>, with the same
+    /// corrections as [`Mpu6050`](crate::Mpu6050)
+    async fn read_rot_i32(&mut self, reg: u8) -> Result<Vector3d<i32>, Mpu6050Error<E>> {
+        if reg == GYRO_REGX_H && self.conv.gyro_needs_temp() {
+            // the temperature model needs the temperature of the same sample
+            let mut buf: [u8; 8] = [0; 8];
+            self.read_bytes(TEMP_OUT_H, &mut buf).await?;
+            let temp = self
+                .conv
+                .temp_from_raw(i16::from_be_bytes([buf[0], buf[1]]));
+            return Ok(self.conv.gyro_counts(read_vec_i16(&buf[2..8]), Some(temp)));
+        }
+
+        let mut buf: [u8; 6] = [0; 6];
+        self.read_bytes(reg, &mut buf).await?;
+        let raw = read_vec_i16(&buf);
+        Ok(match reg {
+            GYRO_REGX_H => self.conv.gyro_counts(raw, None),
+            ACC_REGX_H => self.conv.acc_counts(raw),
+            _ => Vector3d::<i32> {
+                x: raw.x as i32,
+                y: raw.y as i32,
+                z: raw.z as i32,
+            },
+        })
+    }
+
+    /// Accelerometer readings, in g
+    pub async fn get_acc(&mut self) -> Result<Acceleration, Mpu6050Error<E>> {
+        let acc = self.read_rot_i32(ACC_REGX_H).await?;
+        Ok(self.conv.acc_scale(acc))
+    }
+
+    /// Gyro readings, in rad/s
+    pub async fn get_gyro(&mut self) -> Result<AngularVelocity, Mpu6050Error<E>> {
+        let gyro = self.read_rot_i32(GYRO_REGX_H).await?;
+        Ok(self.conv.gyro_scale(gyro))
+    }
+
+    /// Gyro readings in deg/s, a plain vector for the same reason as `Mpu6050::get_gyro_deg`
+    pub async fn get_gyro_deg(&mut self) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        let gyro = self.read_rot_i32(GYRO_REGX_H).await?;
+        Ok(self.conv.gyro_deg_scale(gyro))
+    }
+
+    /// enable, disable temperature measurement of sensor, see `Mpu6050::set_temp_enabled`
+    pub async fn set_temp_enabled(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        let mut byte = self.read_byte(PWR_MGMT_1::ADDR).await?;
+        bits::set_bit(&mut byte, PWR_MGMT_1::TEMP_DIS, !enable);
+        self.write_byte(PWR_MGMT_1::ADDR, byte).await?;
+        self.conv.temp_enabled = enable;
+        Ok(())
+    }
+
+    /// Sets (or clears) the gyro bias temperature model, see `Mpu6050::set_gyro_temp_compensation`
+    pub fn set_gyro_temp_compensation(&mut self, model: Option<GyroTempModel>) {
+        self.conv.gyro_temp_model = model;
+    }
+
+    /// Overrides the temperature conversion, see `Mpu6050::set_temp_calibration`
+    pub fn set_temp_calibration(&mut self, sensitivity: f32, offset: f32) {
+        self.conv.temp_sensitivity = sensitivity;
+        self.conv.temp_offset = offset;
+    }
+
+    /// Sensor Temp in degrees celcius, `TemperatureDisabled` while the sensor is disabled
+    pub async fn get_temp(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        if !self.conv.temp_enabled {
+            return Err(Mpu6050Error::TemperatureDisabled);
+        }
+        let mut buf: [u8; 2] = [0; 2];
+        self.read_bytes(TEMP_OUT_H, &mut buf).await?;
+        Ok(self.conv.temp_from_raw(i16::from_be_bytes(buf)))
+    }
+
+    /// get gyro offsets
+    pub async fn get_gyro_offsets(&mut self) -> Result<Vector3d<i32>, Mpu6050Error<E>> {
+        let mut buf: [u8; 2] = [0; 2];
+        let mut offsets: Vector3d<i32> = Vector3d::<i32>::default();
+
+        self.read_bytes(XG_OFFS_USRH, &mut buf).await?;
+        offsets.x = read_word_2c(&buf[0..2]);
+        self.read_bytes(YG_OFFS_USRH, &mut buf).await?;
+        offsets.y = read_word_2c(&buf[0..2]);
+        self.read_bytes(ZG_OFFS_USRH, &mut buf).await?;
+        offsets.z = read_word_2c(&buf[0..2]);
+
+        Ok(offsets)
+    }
+
+    /// set gyro offsets
+    pub async fn set_gyro_offsets(
+        &mut self,
+        x_offset: i16,
+        y_offset: i16,
+        z_offset: i16,
+    ) -> Result<(), Mpu6050Error<E>> {
+        self.write_word(XG_OFFS_USRH, x_offset as u16).await?;
+        self.write_word(YG_OFFS_USRH, y_offset as u16).await?;
+        self.write_word(ZG_OFFS_USRH, z_offset as u16).await?;
+        Ok(())
+    }
+
+    /// Calibrate gyro and update offsets, see [`Mpu6050::calibrate_gyro`](crate::Mpu6050::calibrate_gyro).
+    /// The delays between readings yield to the executor.
+    pub async fn calibrate_gyro<D: DelayNs, F: FnMut(usize)>(
+        &mut self,
+        delay: &mut D,
+        mut callback: F,
+    ) -> Result<(), Mpu6050Error<E>> {
+        #[cfg(feature = "defmt")]
+        info!("Calibrating gyro");
+
+        // first set current offsets to 0
+        self.set_gyro_offsets(0, 0, 0).await?;
+        self.conv.gyro_fine_tune_offsets = Vector3d::<i32>::default();
+
+        let mut offsets_found = false;
+        let mut calibration_step: usize = 0;
+        let mut mean = Vector3d::<f32>::default();
+        while !offsets_found && calibration_step < CALIBRATION_MAX_STEPS {
+            mean = self.calibrate_mean_sensor(GYRO_REGX_H, delay).await?;
+
+            let offsets = self.get_gyro_offsets().await?;
+            let updated_offsets =
+                gyro_calibration_offsets(offsets, mean, GYRO_CALIBRATION_TARGET_MEAN);
+            self.set_gyro_offsets(
+                updated_offsets.x as i16,
+                updated_offsets.y as i16,
+                updated_offsets.z as i16,
+            )
+            .await?;
+
+            callback(calibration_step);
+
+            offsets_found = calibration_converged(mean, GYRO_CALIBRATION_TARGET_MEAN);
+            calibration_step += 1;
+        }
+
+        if !offsets_found {
+            return Err(Mpu6050Error::CalibrationFailed(mean));
+        }
+        self.conv.gyro_fine_tune_offsets = calibration_residual(mean);
+        Ok(())
+    }
+
+    async fn calibrate_mean_sensor<D: DelayNs>(
+        &mut self,
+        reg: u8,
+        delay: &mut D,
+    ) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        let mut sum: Vector3d<i32> = Vector3d::<i32>::default();
+
+        // discard first readings
+        for _ in 0..CALIBRATION_DISCARD_COUNT {
+            let _ = self.read_rot_i32(reg).await?;
+            delay.delay_ms(CALIBRATION_SAMPLE_DELAY_MS as u32).await;
+        }
+        for _ in 0..CALIBRATION_MEASUREMENT_COUNT {
+            sum += self.read_rot_i32(reg).await?;
+            delay.delay_ms(CALIBRATION_SAMPLE_DELAY_MS as u32).await;
+        }
+        Ok(calibration_mean(sum, CALIBRATION_MEASUREMENT_COUNT))
+    }
+
+    /// Writes word to register
+    pub async fn write_word(&mut self, reg: u8, word_value: u16) -> Result<(), Mpu6050Error<E>> {
+        let data = [reg, (word_value >> 8) as u8, (word_value & 0x00FF) as u8];
+        self.i2c
+            .write(self.slave_addr, &data)
+            .await
+            .map_err(Mpu6050Error::I2c)
+    }
+
+    /// Writes byte to register
+    pub async fn write_byte(&mut self, reg: u8, byte: u8) -> Result<(), Mpu6050Error<E>> {
+        self.i2c
+            .write(self.slave_addr, &[reg, byte])
+            .await
+            .map_err(Mpu6050Error::I2c)
+    }
+
+    /// Write bits data at reg from start_bit to start_bit+length
+    pub async fn write_bits(
+        &mut self,
+        reg: u8,
+        start_bit: u8,
+        length: u8,
+        data: u8,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let mut byte = self.read_byte(reg).await?;
+        bits::set_bits(&mut byte, start_bit, length, data);
+        self.write_byte(reg, byte).await
+    }
+
+    /// Reads byte from register
+    pub async fn read_byte(&mut self, reg: u8) -> Result<u8, Mpu6050Error<E>> {
+        let mut byte: [u8; 1] = [0; 1];
+        self.read_bytes(reg, &mut byte).await?;
+        Ok(byte[0])
+    }
+
+    /// Reads series of bytes into buf from specified reg
+    pub async fn read_bytes(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Mpu6050Error<E>> {
+        self.i2c
+            .write_read(self.slave_addr, &[reg], buf)
+            .await
+            .map_err(Mpu6050Error::I2c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::scale_gyro;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use embedded_hal_async::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Operation};
+
+    /// Register map of a device at 0x68 with auto incrementing register pointer, as the MPU6050 does
+    struct FakeBus {
+        regs: [u8; 128],
+        pointer: usize,
+    }
+
+    impl FakeBus {
+        fn new() -> Self {
+            let mut regs = [0; 128];
+            regs[WHOAMI as usize] = 0x68;
+            FakeBus { regs, pointer: 0 }
+        }
+
+        fn set_word(&mut self, reg: u8, word: i16) {
+            let [h, l] = word.to_be_bytes();
+            self.regs[reg as usize] = h;
+            self.regs[reg as usize + 1] = l;
+        }
+    }
+
+    impl ErrorType for FakeBus {
+        type Error = ErrorKind;
+    }
+
+    impl I2c for FakeBus {
+        async fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            if address != DEFAULT_SLAVE_ADDR {
+                return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+            }
+            for op in operations {
+                match op {
+                    Operation::Write(bytes) => {
+                        if let Some((reg, data)) = bytes.split_first() {
+                            self.pointer = *reg as usize;
+                            for byte in data {
+                                self.regs[self.pointer] = *byte;
+                                self.pointer += 1;
+                            }
+                        }
+                    }
+                    Operation::Read(buf) => {
+                        for byte in buf.iter_mut() {
+                            *byte = self.regs[self.pointer];
+                            self.pointer += 1;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    struct NoDelay;
+
+    impl DelayNs for NoDelay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    /// The fake bus and delay never pend, a single poll completes the future
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future pending"),
+        }
+    }
+
+    #[test]
+    fn async_conversion_test() {
+        let mut bus = FakeBus::new();
+        bus.set_word(ACC_REGX_H, 0x2000);
+        bus.set_word(ACC_REGX_H + 4, 0x2000 + 100);
+        // 26.53°C
+        bus.set_word(TEMP_OUT_H, -3400);
+        bus.set_word(GYRO_REGX_H, 100);
+        bus.set_word(GYRO_REGX_H + 4, -50);
+
+        let mut mpu = Mpu6050Async::new_with_sens(bus, AccelRange::G4, GyroRange::D500);
+        block_on(mpu.init(&mut NoDelay)).unwrap();
+        // init writes the ranges given to the constructor
+        assert_eq!(mpu.i2c.regs[ACCEL_CONFIG::ADDR as usize], 0x08);
+        assert_eq!(mpu.i2c.regs[GYRO_CONFIG::ADDR as usize], 0x08);
+
+        // fine tune offsets, as left by calibration
+        mpu.conv.acc_fine_tune_offsets = Vector3d {
+            x: 0,
+            y: 0,
+            z: -100,
+        };
+        mpu.conv.gyro_fine_tune_offsets = Vector3d { x: 5, y: 0, z: 0 };
+        let acc = block_on(mpu.get_acc()).unwrap();
+        assert_eq!(acc.x, 1.0);
+        assert_eq!(acc.z, 1.0);
+        let gyro = block_on(mpu.get_gyro_deg()).unwrap();
+        assert_eq!(gyro.x, scale_gyro(105, GyroRange::D500));
+        assert_eq!(gyro.z, scale_gyro(-50, GyroRange::D500));
+
+        // bias at 26.53°C: 10 + 1 * 10 counts on x
+        mpu.set_gyro_temp_compensation(Some(GyroTempModel {
+            slope: Vector3d {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            intercept: Vector3d {
+                x: 10.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            ref_temp: 16.53,
+        }));
+        let gyro = block_on(mpu.get_gyro_deg()).unwrap();
+        assert_eq!(gyro.x, scale_gyro(85, GyroRange::D500));
+
+        mpu.set_temp_calibration(170.0, 40.0);
+        let temp = block_on(mpu.get_temp()).unwrap();
+        assert_eq!(temp, 20.0);
+
+        // no temperature compensation and no temperature while the sensor is disabled
+        block_on(mpu.set_temp_enabled(false)).unwrap();
+        assert_eq!(mpu.i2c.regs[PWR_MGMT_1::ADDR as usize], 0x09);
+        let gyro = block_on(mpu.get_gyro_deg()).unwrap();
+        assert_eq!(gyro.x, scale_gyro(105, GyroRange::D500));
+        assert!(matches!(
+            block_on(mpu.get_temp()),
+            Err(Mpu6050Error::TemperatureDisabled)
+        ));
+    }
+}
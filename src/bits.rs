@@ -1,13 +1,26 @@
 //! Bit operations on registers
 //! Mostly taken from https://github.com/jrowberg/i2cdevlib/blob/master/Arduino/I2Cdev/I2Cdev.cpp
 //! updated and tested
+//!
+//! Public for register access the driver doesn't wrap yet, together with `Mpu6050::read_byte` and
+//! `Mpu6050::write_byte`. As in i2cdevlib, a bit field is given by its highest bit (bit_start) and its
+//! length, the same as the `BitBlock`s in [`device`](crate::device):
+//! ```
+//! use mpu6050::bits;
+//! use mpu6050::device::PWR_MGMT_2;
+//!
+//! let mut byte = 0x07;
+//! bits::set_bits(&mut byte, PWR_MGMT_2::LP_WAKE_CTRL.bit, PWR_MGMT_2::LP_WAKE_CTRL.length, 2);
+//! assert_eq!(byte, 0x87);
+//! assert_eq!(bits::get_bits(byte, 7, 2), 2);
+//! ```
 
 /// get bit n of byte
 pub fn get_bit(byte: u8, n: u8) -> u8 {
     (byte >> n) & 1
 }
 
-/// get bits start - start+length from byte
+/// get bits bit_start - (bit_start-length+1) from byte, shifted down to bit 0
 pub fn get_bits(mut byte: u8, bit_start: u8, length: u8) -> u8 {
     // 01101001 read byte
     // 76543210 bit numbers
@@ -26,7 +39,7 @@ pub fn get_bits(mut byte: u8, bit_start: u8, length: u8) -> u8 {
         bit_start - length + 1
     };
     let mask: u8 = ((1 << length) - 1) << mask_shift;
-    byte &= mask as u8;
+    byte &= mask;
     byte >>= mask_shift;
     byte
 }
@@ -40,7 +53,7 @@ pub fn set_bit(byte: &mut u8, n: u8, enable: bool) {
     }
 }
 
-/// Fill bits bitstart-bitstart+length in byte with data
+/// Fill bits bit_start - (bit_start-length+1) in byte with the low length bits of data
 pub fn set_bits(byte: &mut u8, bit_start: u8, length: u8, mut data: u8) {
     /*
              010 value to write
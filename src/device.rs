@@ -7,6 +7,8 @@
 //! * Register map (rev 3.2): https://arduino.ua/docs/RM-MPU-6000A.pdf
 //! * Datasheet (rev 3.2): https://www.cdiweb.com/datasheets/invensense/ps-mpu-6000a.pdf
 
+use crate::bits;
+
 /// Gyro Sensitivity
 ///
 /// Measurements are scaled like this:
@@ -37,10 +39,20 @@ pub const TEMP_OFFSET: f32 = 36.53;
 /// Temperature Sensitivity
 pub const TEMP_SENSITIVITY: f32 = 340.;
 
+/// Sample Rate Divider Register
+pub const SMPLRT_DIV: u8 = 0x19;
+/// Free Fall Threshold Register
+pub const FF_THR: u8 = 0x1D;
+/// Free Fall Duration Register
+pub const FF_DUR: u8 = 0x1E;
 /// Motion Threshold Register
 pub const MOT_THR: u8 = 0x1F;
 /// Motion Duration Detection Register
 pub const MOT_DUR: u8 = 0x20;
+/// Zero Motion Threshold Register
+pub const ZRMOT_THR: u8 = 0x21;
+/// Zero Motion Duration Register
+pub const ZRMOT_DUR: u8 = 0x22;
 /// High Byte Register Gyro x orientation
 pub const GYRO_REGX_H: u8 = 0x43;
 /// High Byte Register Gyro y orientation
@@ -57,8 +69,28 @@ pub const ACC_REGZ_H: u8 = 0x3f;
 pub const TEMP_OUT_H: u8 = 0x41;
 /// Slave address of Mpu6050
 pub const DEFAULT_SLAVE_ADDR: u8 = 0x68;
+/// Slave address of Mpu6050 with AD0 pulled high
+pub const ALT_SLAVE_ADDR: u8 = 0x69;
 /// Internal register to check slave addr
 pub const WHOAMI: u8 = 0x75;
+/// Expected WHOAMI register content. Doesn't depend on the A0 pin, i.e. the slave address
+pub const MPU6050_CHIP_ID: u8 = 0x68;
+/// WHOAMI register content of the register compatible MPU6500
+pub const MPU6500_CHIP_ID: u8 = 0x70;
+/// WHOAMI register content of the MPU9250 (MPU6500 core plus magnetometer)
+pub const MPU9250_CHIP_ID: u8 = 0x71;
+/// First register of the configuration register block read by `dump_registers` (SMPLRT_DIV)
+pub const REGISTER_DUMP_START: u8 = 0x19;
+/// Number of registers read by `dump_registers`, SMPLRT_DIV (0x19) through PWR_MGMT_2 (0x6C)
+pub const REGISTER_DUMP_LEN: usize = 84;
+/// First register of the external sensor data, read by the auxiliary I2C master (0x49 - 0x60)
+pub const EXT_SENS_DATA_00: u8 = 0x49;
+/// Number of external sensor data registers
+pub const EXT_SENS_DATA_LEN: u8 = 24;
+/// High Byte Register FIFO count
+pub const FIFO_COUNT_H: u8 = 0x72;
+/// FIFO read/write register
+pub const FIFO_R_W: u8 = 0x74;
 
 /// High Byte Gyro X Offset Register
 pub const XG_OFFS_USRH: u8 = 0x13;
@@ -66,6 +98,20 @@ pub const XG_OFFS_USRH: u8 = 0x13;
 pub const YG_OFFS_USRH: u8 = 0x15;
 /// High Byte Gyro Z Offset Register
 pub const ZG_OFFS_USRH: u8 = 0x17;
+/// High Byte Accel X Offset Register
+pub const XA_OFFS_USRH: u8 = 0x06;
+/// High Byte Accel Y Offset Register
+pub const YA_OFFS_USRH: u8 = 0x08;
+/// High Byte Accel Z Offset Register
+pub const ZA_OFFS_USRH: u8 = 0x0A;
+/// Product ID register, undocumented. The low nibble is the silicon revision on parts that report
+/// revision 0 in the accel offset reserved bits, revisions below 4 have half accel sensitivity
+/// (InvenSense MPL, inv_mpu.c)
+pub const PRODUCT_ID: u8 = 0x0C;
+/// Accel offset registers are in +-16g format, i.e. 2048 LSB/g
+pub const ACCEL_OFFS_SENS: f32 = 2048.;
+/// Bit 0 of the accel offset low byte is reserved and must be preserved on write
+pub const ACCEL_OFFS_RESERVED_MASK: u16 = 0x0001;
 
 
 /// Describes a bit block from bit number 'bit' to 'bit'+'length'
@@ -88,6 +134,29 @@ impl CONFIG {
     pub const DLPF_CFG: BitBlock = BitBlock { bit: 2, length: 3 };
 }
 
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug)]
+/// Register 13 to 16: Self Test Registers, factory trim test values.
+/// SELF_TEST_X, SELF_TEST_Y and SELF_TEST_Z are followed by SELF_TEST_A
+pub struct SELF_TEST;
+
+impl SELF_TEST {
+    /// Base Address, SELF_TEST_X
+    pub const ADDR: u8 = 0x0d;
+    /// SELF_TEST_X/Y/Z: upper 3 bits of the accel test value
+    pub const A_TEST_H: BitBlock = BitBlock { bit: 7, length: 3 };
+    /// SELF_TEST_X/Y/Z: gyro test value
+    pub const G_TEST: BitBlock = BitBlock { bit: 4, length: 5 };
+    /// SELF_TEST_A: lower 2 bits of the x axis accel test value
+    pub const XA_TEST_L: BitBlock = BitBlock { bit: 5, length: 2 };
+    /// SELF_TEST_A: lower 2 bits of the y axis accel test value
+    pub const YA_TEST_L: BitBlock = BitBlock { bit: 3, length: 2 };
+    /// SELF_TEST_A: lower 2 bits of the z axis accel test value
+    pub const ZA_TEST_L: BitBlock = BitBlock { bit: 1, length: 2 };
+    /// Maximum deviation of the self test response from the factory trim in %
+    pub const MAX_DEVIATION: f32 = 14.0;
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug)]
 /// Register 27: Gyro Config
@@ -125,6 +194,119 @@ impl ACCEL_CONFIG {
     pub const ACCEL_HPF: BitBlock = BitBlock { bit: 2, length: 3 };
 }
 
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug)]
+/// Register 35: FIFO Enable, which sensor measurements are loaded into the FIFO buffer
+pub struct FIFO_EN;
+
+impl FIFO_EN {
+    /// Base Address
+    pub const ADDR: u8 = 0x23;
+    /// TEMP_OUT_H and TEMP_OUT_L are written into the FIFO
+    pub const TEMP_FIFO_EN: u8 = 7;
+    /// GYRO_XOUT_H and GYRO_XOUT_L are written into the FIFO
+    pub const XG_FIFO_EN: u8 = 6;
+    /// GYRO_YOUT_H and GYRO_YOUT_L are written into the FIFO
+    pub const YG_FIFO_EN: u8 = 5;
+    /// GYRO_ZOUT_H and GYRO_ZOUT_L are written into the FIFO
+    pub const ZG_FIFO_EN: u8 = 4;
+    /// ACCEL_XOUT_H through ACCEL_ZOUT_L are written into the FIFO
+    pub const ACCEL_FIFO_EN: u8 = 3;
+    /// EXT_SENS_DATA of slave 2 is written into the FIFO
+    pub const SLV2_FIFO_EN: u8 = 2;
+    /// EXT_SENS_DATA of slave 1 is written into the FIFO
+    pub const SLV1_FIFO_EN: u8 = 1;
+    /// EXT_SENS_DATA of slave 0 is written into the FIFO
+    pub const SLV0_FIFO_EN: u8 = 0;
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug)]
+/// Register 36: I2C Master Control
+pub struct I2C_MST_CTRL;
+
+impl I2C_MST_CTRL {
+    /// Base Address
+    pub const ADDR: u8 = 0x24;
+    /// Enable multi-master capability
+    pub const MULT_MST_EN: u8 = 7;
+    /// Delay the data ready interrupt until external sensor data is loaded
+    pub const WAIT_FOR_ES: u8 = 6;
+    /// EXT_SENS_DATA of slave 3 is written into the FIFO
+    pub const SLV_3_FIFO_EN: u8 = 5;
+    /// Stop (1) or restart (0) between reads of slaves
+    pub const I2C_MST_P_NSR: u8 = 4;
+    /// I2C master clock divider
+    pub const I2C_MST_CLK: BitBlock = BitBlock { bit: 3, length: 4 };
+    /// I2C_MST_CLK value for a 400kHz master clock
+    pub const CLK_400KHZ: u8 = 13;
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug)]
+/// Registers 37 to 48: I2C Slave 0 - 3 Control. Every slave has an ADDR, REG and CTRL register,
+/// slave n starts at ADDR + n * SLOT_SIZE
+pub struct I2C_SLV;
+
+impl I2C_SLV {
+    /// Base Address, I2C_SLV0_ADDR
+    pub const ADDR: u8 = 0x25;
+    /// Number of registers per slave
+    pub const SLOT_SIZE: u8 = 3;
+    /// Number of slaves with this layout (slave 4 is different)
+    pub const SLOTS: u8 = 4;
+    /// Offset of I2C_SLVn_REG, the slave register to start reading from
+    pub const REG_OFFSET: u8 = 1;
+    /// Offset of I2C_SLVn_CTRL
+    pub const CTRL_OFFSET: u8 = 2;
+    /// I2C_SLVn_ADDR: read (1) or write (0) transfer
+    pub const RW: u8 = 7;
+    /// I2C_SLVn_CTRL: enable slave
+    pub const EN: u8 = 7;
+    /// I2C_SLVn_CTRL: swap bytes of words
+    pub const BYTE_SW: u8 = 6;
+    /// I2C_SLVn_CTRL: read/write data only, without writing the register address
+    pub const REG_DIS: u8 = 5;
+    /// I2C_SLVn_CTRL: word grouping of swapped bytes
+    pub const GRP: u8 = 4;
+    /// I2C_SLVn_CTRL: number of bytes to read
+    pub const LEN: BitBlock = BitBlock { bit: 3, length: 4 };
+    /// I2C_SLV0_DO, byte written by slave 0 in write mode, slave n at DO_ADDR + n
+    pub const DO_ADDR: u8 = 0x63;
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug)]
+/// Registers of the AK8963 / AK8975 magnetometer of the MPU9250 / MPU9150, reached through the auxiliary I2C bus
+pub struct AK89XX;
+
+impl AK89XX {
+    /// I2C address
+    pub const ADDR: u8 = 0x0C;
+    /// Device ID register
+    pub const WIA: u8 = 0x00;
+    /// Device ID of both models
+    pub const WIA_ID: u8 = 0x48;
+    /// First data register, HXL. Data is little endian, x, y, z
+    pub const HXL: u8 = 0x03;
+    /// Bytes read per measurement, HXL through ST2. Reading ST2 ends the measurement
+    pub const DATA_LEN: u8 = 7;
+    /// ST2: magnetic sensor overflow
+    pub const ST2_HOFL: u8 = 3;
+    /// Control register, operation mode
+    pub const CNTL: u8 = 0x0A;
+    /// First sensitivity adjustment register, ASAX. Readable in fuse ROM mode only
+    pub const ASAX: u8 = 0x10;
+    /// CNTL: power down mode
+    pub const MODE_POWER_DOWN: u8 = 0x00;
+    /// CNTL: single measurement mode
+    pub const MODE_SINGLE: u8 = 0x01;
+    /// CNTL: fuse ROM access mode
+    pub const MODE_FUSE_ROM: u8 = 0x0F;
+    /// CNTL (AK8963 only): continuous measurement mode 2 (100Hz), 16 bit output
+    pub const MODE_CONTINUOUS_16BIT: u8 = 0x16;
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug)]
 /// Register 55: INT Pin / Bypass Enable Configuration
@@ -166,6 +348,9 @@ impl INT_ENABLE {
     /// Generate iterrrupt when Zero Motion Detection
     pub const ZMOT_EN: u8 = 5;
     /// Generate iterrupt when FIFO buffer overflow
+    pub const FIFO_OFLOW_EN: u8 = 4;
+    /// Generate iterrupt when FIFO buffer overflow
+    #[deprecated(note = "misspelled, use FIFO_OFLOW_EN")]
     pub const FIFO_OFLOW_END: u8 = 4;
     /// this  bit enables  any  of  the  I2C  Masterinterrupt  sources  to generate an interrupt
     pub const I2C_MST_INT_EN: u8 = 3;
@@ -190,6 +375,9 @@ impl INT_STATUS {
     /// FIFO buffer overflow
     pub const FIFO_OFLOW_INT: u8 = 4;
     /// i2c master interrupt has been generated
+    pub const I2C_MST_INT: u8 = 3;
+    /// i2c master interrupt has been generated
+    #[deprecated(note = "misspelled, use I2C_MST_INT")]
     pub const I2C_MSF_INT: u8 = 3;
     /// Data is ready
     pub const DATA_RDY_INT: u8 = 0;
@@ -235,6 +423,28 @@ impl MOT_DETECT_CONTROL {
     pub const MOT_COUNT: BitBlock = BitBlock { bit: 1, length: 2 };
 }
 
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug)]
+/// Register 106: User Control
+pub struct USER_CTRL;
+
+impl USER_CTRL {
+    /// Base Address
+    pub const ADDR: u8 = 0x6a;
+    /// Enable FIFO operations
+    pub const FIFO_EN: u8 = 6;
+    /// Enable I2C master mode
+    pub const I2C_MST_EN: u8 = 5;
+    /// Disable the primary I2C interface (MPU6000 SPI only)
+    pub const I2C_IF_DIS: u8 = 4;
+    /// Reset the FIFO buffer while FIFO_EN is 0. Clears automatically
+    pub const FIFO_RESET: u8 = 2;
+    /// Reset the I2C master while I2C_MST_EN is 0. Clears automatically
+    pub const I2C_MST_RESET: u8 = 1;
+    /// Reset the signal paths and clear the sensor registers. Clears automatically
+    pub const SIG_COND_RESET: u8 = 0;
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug)]
 /// Register 107: Power Management 1
@@ -257,7 +467,7 @@ impl PWR_MGMT_1 {
 
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug)]
-/// Register 107: Power Management 2
+/// Register 108: Power Management 2
 pub struct PWR_MGMT_2;
 
 impl PWR_MGMT_2 {
@@ -281,7 +491,8 @@ impl PWR_MGMT_2 {
 
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-/// Wake values
+/// Wake values, as listed in register map rev 3.2. Later revisions list different frequencies
+#[deprecated(note = "use LpWakeCtrl, which has the wake frequencies of register map rev 4.2")]
 pub enum LP_WAKE_CTRL {
     /// 1.25 Hz
     _1P25 = 0,
@@ -293,6 +504,43 @@ pub enum LP_WAKE_CTRL {
     _10,
 }
 
+/// Wake up frequency in accelerometer only low power (cycle) mode, register map rev 4.2
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LpWakeCtrl {
+    /// 1.25 Hz
+    _1P25 = 0,
+    /// 5 Hz
+    _5 = 1,
+    /// 20 Hz
+    _20 = 2,
+    /// 40 Hz
+    _40 = 3,
+}
+
+impl LpWakeCtrl {
+    /// Wake up frequency in Hz, the accel output rate in cycle mode
+    pub fn frequency(&self) -> f32 {
+        match &self {
+            LpWakeCtrl::_1P25 => 1.25,
+            LpWakeCtrl::_5 => 5.0,
+            LpWakeCtrl::_20 => 20.0,
+            LpWakeCtrl::_40 => 40.0,
+        }
+    }
+}
+
+impl From<u8> for LpWakeCtrl {
+    fn from(freq: u8) -> Self {
+        match freq {
+            0 => LpWakeCtrl::_1P25,
+            1 => LpWakeCtrl::_5,
+            2 => LpWakeCtrl::_20,
+            3 => LpWakeCtrl::_40,
+            _ => LpWakeCtrl::_1P25,
+        }
+    }
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 /// Accelerometer High Pass Filter Values
@@ -326,7 +574,394 @@ impl From<u8> for ACCEL_HPF {
     }
 }
 
+/// Settings applied by [`Mpu6050::init_with_config`](crate::Mpu6050::init_with_config).
+/// Ranges left at None keep the ones the driver was constructed with, all other None fields
+/// leave the device untouched. The default matches [`Mpu6050::init`](crate::Mpu6050::init)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Mpu6050Config {
+    /// Accelerometer range
+    pub accel_range: Option<AccelRange>,
+    /// Gyro range
+    pub gyro_range: Option<GyroRange>,
+    /// Digital low pass filter
+    pub dlpf: Option<DLPF>,
+    /// Sample rate in Hz, see [`Mpu6050::set_sample_rate`](crate::Mpu6050::set_sample_rate)
+    pub sample_rate: Option<u16>,
+    /// Clock source, init selects PLL with X axis gyro reference otherwise
+    pub clock_source: Option<CLKSEL>,
+    /// Accelerometer high pass filter, `_RESET` fails while motion detection is enabled
+    pub accel_hpf: Option<ACCEL_HPF>,
+    /// Time to wait after waking the device in ms, 100ms if None
+    pub wake_delay_ms: Option<u16>,
+    /// Skip waking the device (and the wake delay), e.g. after a warm reset with the device still awake.
+    /// Leaves the clock source as is, unless clock_source is given
+    pub skip_wake: bool,
+}
+
+/// Configuration read by [`Mpu6050::save_config`](crate::Mpu6050::save_config), to be re-applied with
+/// [`Mpu6050::restore_config`](crate::Mpu6050::restore_config), e.g. after a device reset
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigSnapshot {
+    /// Accelerometer range
+    pub accel_range: AccelRange,
+    /// Gyro range
+    pub gyro_range: GyroRange,
+    /// Digital low pass filter
+    pub dlpf: DLPF,
+    /// Sample rate divider (SMPLRT_DIV)
+    pub sample_rate_divider: u8,
+    /// Gyro offsets x, y, z
+    pub gyro_offsets: [i16; 3],
+    /// Accel offsets x, y, z
+    pub accel_offsets: [i16; 3],
+    /// Clock source
+    pub clock_source: CLKSEL,
+    /// Enabled interrupt sources
+    pub interrupt_enable: InterruptEnable,
+}
+
+/// Motion detection settings
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MotionConfig {
+    /// Motion threshold (MOT_THR), LSB = 2mg
+    pub threshold: u8,
+    /// Motion duration (MOT_DUR), LSB = 1ms
+    pub duration: u8,
+    /// Accel high pass filter, motion detection works on the filtered accel data.
+    /// Must not be `_RESET`, the filter output is always 0 then
+    pub accel_hpf: ACCEL_HPF,
+}
+
+impl Default for MotionConfig {
+    fn default() -> Self {
+        MotionConfig {
+            threshold: 10,
+            duration: 40,
+            accel_hpf: ACCEL_HPF::_5,
+        }
+    }
+}
+
+/// INT pin configuration (INT_PIN_CFG). Default: active high, push-pull, 50us pulse, cleared by reading INT_STATUS
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct IntPinConfig {
+    /// INT_LEVEL: the INT pin is active low
+    pub active_low: bool,
+    /// INT_OPEN: the INT pin is open drain, instead of push-pull
+    pub open_drain: bool,
+    /// LATCH_INT_EN: the INT pin is held active until the interrupt is cleared, instead of a 50us pulse
+    pub latch_until_cleared: bool,
+    /// INT_RD_CLEAR: the interrupt status is cleared by any read operation, instead of only by reading INT_STATUS
+    pub clear_on_any_read: bool,
+}
+
+/// Device models with an accel/gyro core compatible to the MPU6050, identified by WHOAMI
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DeviceModel {
+    /// MPU6050, WHOAMI 0x68
+    Mpu6050,
+    /// MPU6500, WHOAMI 0x70
+    Mpu6500,
+    /// MPU9250, WHOAMI 0x71
+    Mpu9250,
+}
+
+impl DeviceModel {
+    /// Model for a WHOAMI value, None for unknown chip ids
+    pub fn from_chip_id(chip_id: u8) -> Option<DeviceModel> {
+        match chip_id {
+            MPU6050_CHIP_ID => Some(DeviceModel::Mpu6050),
+            MPU6500_CHIP_ID => Some(DeviceModel::Mpu6500),
+            MPU9250_CHIP_ID => Some(DeviceModel::Mpu9250),
+            _ => None,
+        }
+    }
+
+    /// Expected WHOAMI value of the model
+    pub fn chip_id(self) -> u8 {
+        match self {
+            DeviceModel::Mpu6050 => MPU6050_CHIP_ID,
+            DeviceModel::Mpu6500 => MPU6500_CHIP_ID,
+            DeviceModel::Mpu9250 => MPU9250_CHIP_ID,
+        }
+    }
+}
+
+/// Magnetometer attached to the auxiliary I2C bus, see [`Mpu6050::setup_magnetometer`](crate::Mpu6050::setup_magnetometer)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Magnetometer {
+    /// AK8963 of the MPU9250, used in continuous 16 bit mode
+    AK8963,
+    /// AK8975 of the MPU9150, single measurements triggered every sample
+    AK8975,
+}
+
+impl Magnetometer {
+    /// Sensitivity in µT/LSB, before the factory sensitivity adjustment
+    pub fn sensitivity(self) -> f32 {
+        match self {
+            Magnetometer::AK8963 => 0.15,
+            Magnetometer::AK8975 => 0.3,
+        }
+    }
+}
+
+/// Persistent USER_CTRL bits. The self clearing reset bits are written by
+/// [`Mpu6050::reset_fifo`](crate::Mpu6050::reset_fifo), [`Mpu6050::reset_i2c_master`](crate::Mpu6050::reset_i2c_master)
+/// and [`Mpu6050::reset_signal_path`](crate::Mpu6050::reset_signal_path)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct UserCtrl {
+    /// FIFO_EN: FIFO operations enabled
+    pub fifo_enabled: bool,
+    /// I2C_MST_EN: auxiliary I2C master enabled
+    pub i2c_master_enabled: bool,
+    /// I2C_IF_DIS: primary I2C interface disabled (MPU6000 SPI only, keep false on the MPU6050)
+    pub i2c_if_disabled: bool,
+}
+
+impl From<u8> for UserCtrl {
+    fn from(byte: u8) -> Self {
+        UserCtrl {
+            fifo_enabled: bits::get_bit(byte, USER_CTRL::FIFO_EN) != 0,
+            i2c_master_enabled: bits::get_bit(byte, USER_CTRL::I2C_MST_EN) != 0,
+            i2c_if_disabled: bits::get_bit(byte, USER_CTRL::I2C_IF_DIS) != 0,
+        }
+    }
+}
+
+/// Measurements per FIFO sample, matching the sources selected with
+/// [`Mpu6050::set_fifo_sources`](crate::Mpu6050::set_fifo_sources) (gyro with all 3 axes)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct FifoLayout {
+    /// ACCEL_XOUT_H through ACCEL_ZOUT_L, 6 bytes
+    pub accel: bool,
+    /// TEMP_OUT_H and TEMP_OUT_L, 2 bytes
+    pub temp: bool,
+    /// GYRO_XOUT_H through GYRO_ZOUT_L, 6 bytes
+    pub gyro: bool,
+}
+
+impl FifoLayout {
+    /// Bytes per sample
+    pub const fn sample_len(&self) -> usize {
+        (self.accel as usize) * 6 + (self.temp as usize) * 2 + (self.gyro as usize) * 6
+    }
+}
+
+/// Axis and direction that triggered motion detection (MOT_DETECT_STATUS)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MotionDetectStatus {
+    /// Motion in the negative X direction
+    pub x_neg: bool,
+    /// Motion in the positive X direction
+    pub x_pos: bool,
+    /// Motion in the negative Y direction
+    pub y_neg: bool,
+    /// Motion in the positive Y direction
+    pub y_pos: bool,
+    /// Motion in the negative Z direction
+    pub z_neg: bool,
+    /// Motion in the positive Z direction
+    pub z_pos: bool,
+    /// Zero motion detected
+    pub zero_motion: bool,
+}
+
+impl From<u8> for MotionDetectStatus {
+    fn from(byte: u8) -> Self {
+        MotionDetectStatus {
+            x_neg: bits::get_bit(byte, MOT_DETECT_STATUS::MOT_XNEG) != 0,
+            x_pos: bits::get_bit(byte, MOT_DETECT_STATUS::MOT_XPOS) != 0,
+            y_neg: bits::get_bit(byte, MOT_DETECT_STATUS::MOT_YNEG) != 0,
+            y_pos: bits::get_bit(byte, MOT_DETECT_STATUS::MOT_YPOS) != 0,
+            z_neg: bits::get_bit(byte, MOT_DETECT_STATUS::MOT_ZNEG) != 0,
+            z_pos: bits::get_bit(byte, MOT_DETECT_STATUS::MOT_ZPOS) != 0,
+            zero_motion: bits::get_bit(byte, MOT_DETECT_STATUS::MOT_ZRMOT) != 0,
+        }
+    }
+}
+
+/// Interrupt sources to enable (INT_ENABLE)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct InterruptEnable {
+    /// DATA_RDY_EN: all sensor registers have been written
+    pub data_ready: bool,
+    /// FIFO_OFLOW_EN: FIFO buffer overflow, see [`Mpu6050::fifo_overflow`](crate::Mpu6050::fifo_overflow)
+    pub fifo_overflow: bool,
+    /// I2C_MST_INT_EN: any of the I2C master interrupt sources (I2C_MST_STATUS), e.g. a slave NACK
+    pub i2c_mst: bool,
+    /// MOT_EN: motion detection
+    pub motion: bool,
+    /// ZMOT_EN: zero motion detection
+    pub zero_motion: bool,
+    /// FF_EN: free fall detection
+    pub free_fall: bool,
+}
+
+impl From<u8> for InterruptEnable {
+    fn from(byte: u8) -> Self {
+        InterruptEnable {
+            data_ready: bits::get_bit(byte, INT_ENABLE::DATA_RDY_EN) != 0,
+            fifo_overflow: bits::get_bit(byte, INT_ENABLE::FIFO_OFLOW_EN) != 0,
+            i2c_mst: bits::get_bit(byte, INT_ENABLE::I2C_MST_INT_EN) != 0,
+            motion: bits::get_bit(byte, INT_ENABLE::MOT_EN) != 0,
+            zero_motion: bits::get_bit(byte, INT_ENABLE::ZMOT_EN) != 0,
+            free_fall: bits::get_bit(byte, INT_ENABLE::FF_EN) != 0,
+        }
+    }
+}
+
+/// Interrupts that have been generated (INT_STATUS)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct InterruptStatus {
+    /// DATA_RDY_INT: all sensor registers have been written
+    pub data_ready: bool,
+    /// FIFO_OFLOW_INT: FIFO buffer overflow
+    pub fifo_overflow: bool,
+    /// I2C_MST_INT: I2C master interrupt
+    pub i2c_mst: bool,
+    /// MOT_INT: motion detected
+    pub motion: bool,
+    /// ZMOT_INT: zero motion detected
+    pub zero_motion: bool,
+    /// FF_INT: free fall detected
+    pub free_fall: bool,
+}
+
+impl From<u8> for InterruptStatus {
+    fn from(byte: u8) -> Self {
+        InterruptStatus {
+            data_ready: bits::get_bit(byte, INT_STATUS::DATA_RDY_INT) != 0,
+            fifo_overflow: bits::get_bit(byte, INT_STATUS::FIFO_OFLOW_INT) != 0,
+            i2c_mst: bits::get_bit(byte, INT_STATUS::I2C_MST_INT) != 0,
+            motion: bits::get_bit(byte, INT_STATUS::MOT_INT) != 0,
+            zero_motion: bits::get_bit(byte, INT_STATUS::ZMOT_INT) != 0,
+            free_fall: bits::get_bit(byte, INT_STATUS::FF_INT) != 0,
+        }
+    }
+}
+
 #[allow(non_camel_case_types)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// Digital Low Pass Filter Values, for accel and gyro. Bandwidths as (accel, gyro)
+pub enum DLPF {
+    /// Filter disabled: 260 Hz, 256 Hz. Gyro output rate 8 kHz
+    DISABLED = 0,
+    /// Bandwidth 184 Hz, 188 Hz. Gyro output rate 1 kHz
+    _184 = 1,
+    /// Bandwidth 94 Hz, 98 Hz. Gyro output rate 1 kHz
+    _94 = 2,
+    /// Bandwidth 44 Hz, 42 Hz. Gyro output rate 1 kHz
+    _44 = 3,
+    /// Bandwidth 21 Hz, 20 Hz. Gyro output rate 1 kHz
+    _21 = 4,
+    /// Bandwidth 10 Hz, 10 Hz. Gyro output rate 1 kHz
+    _10 = 5,
+    /// Bandwidth 5 Hz, 5 Hz. Gyro output rate 1 kHz
+    _5 = 6,
+}
+
+impl From<u8> for DLPF {
+    fn from(cfg: u8) -> Self {
+        match cfg {
+            0 => DLPF::DISABLED,
+            1 => DLPF::_184,
+            2 => DLPF::_94,
+            3 => DLPF::_44,
+            4 => DLPF::_21,
+            5 => DLPF::_10,
+            6 => DLPF::_5,
+            // 7 is reserved, and behaves like 0
+            _ => DLPF::DISABLED,
+        }
+    }
+}
+
+impl DLPF {
+    /// Gyro output rate in Hz for this filter setting, the base of the sample rate
+    pub fn gyro_output_rate(&self) -> u16 {
+        match &self {
+            DLPF::DISABLED => 8000,
+            _ => 1000,
+        }
+    }
+
+    /// Accelerometer bandwidth in Hz, register map table of CONFIG
+    pub fn accel_bandwidth(&self) -> f32 {
+        self.table().0
+    }
+
+    /// Accelerometer delay in ms, register map table of CONFIG
+    pub fn accel_delay_ms(&self) -> f32 {
+        self.table().1
+    }
+
+    /// Gyro bandwidth in Hz, register map table of CONFIG
+    pub fn gyro_bandwidth(&self) -> f32 {
+        self.table().2
+    }
+
+    /// Gyro delay in ms, register map table of CONFIG
+    pub fn gyro_delay_ms(&self) -> f32 {
+        self.table().3
+    }
+
+    /// (accel bandwidth, accel delay, gyro bandwidth, gyro delay)
+    fn table(&self) -> (f32, f32, f32, f32) {
+        match &self {
+            DLPF::DISABLED => (260.0, 0.0, 256.0, 0.98),
+            DLPF::_184 => (184.0, 2.0, 188.0, 1.9),
+            DLPF::_94 => (94.0, 3.0, 98.0, 2.8),
+            DLPF::_44 => (44.0, 4.9, 42.0, 4.8),
+            DLPF::_21 => (21.0, 8.5, 20.0, 8.3),
+            DLPF::_10 => (10.0, 13.8, 10.0, 13.4),
+            DLPF::_5 => (5.0, 19.0, 5.0, 18.6),
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// External Frame Synchronization (FSYNC) Values: the FSYNC pin state is latched into the LSB of this register
+pub enum ExtSync {
+    /// FSYNC input disabled
+    DISABLED = 0,
+    /// TEMP_OUT_L[0]
+    TEMP_OUT_L = 1,
+    /// GYRO_XOUT_L[0]
+    GYRO_XOUT_L = 2,
+    /// GYRO_YOUT_L[0]
+    GYRO_YOUT_L = 3,
+    /// GYRO_ZOUT_L[0]
+    GYRO_ZOUT_L = 4,
+    /// ACCEL_XOUT_L[0]
+    ACCEL_XOUT_L = 5,
+    /// ACCEL_YOUT_L[0]
+    ACCEL_YOUT_L = 6,
+    /// ACCEL_ZOUT_L[0]
+    ACCEL_ZOUT_L = 7,
+}
+
+impl From<u8> for ExtSync {
+    fn from(set: u8) -> Self {
+        match set {
+            1 => ExtSync::TEMP_OUT_L,
+            2 => ExtSync::GYRO_XOUT_L,
+            3 => ExtSync::GYRO_YOUT_L,
+            4 => ExtSync::GYRO_ZOUT_L,
+            5 => ExtSync::ACCEL_XOUT_L,
+            6 => ExtSync::ACCEL_YOUT_L,
+            7 => ExtSync::ACCEL_ZOUT_L,
+            _ => ExtSync::DISABLED,
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 /// Clock Source Select Values
 pub enum CLKSEL {
@@ -365,6 +1000,7 @@ impl From<u8> for CLKSEL {
 }
 
 /// Defines accelerometer range/sensivity
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum AccelRange {
     /// 2G
@@ -378,6 +1014,7 @@ pub enum AccelRange {
 }
 
 /// Defines gyro range/sensitivity
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum GyroRange {
     /// 250 degrees
@@ -414,9 +1051,23 @@ impl From<u8> for AccelRange {
     }
 }
 
+impl From<GyroRange> for u8 {
+    /// FS_SEL register value, inverse of `From<u8>`
+    fn from(range: GyroRange) -> Self {
+        range as u8
+    }
+}
+
+impl From<AccelRange> for u8 {
+    /// AFS_SEL register value, inverse of `From<u8>`
+    fn from(range: AccelRange) -> Self {
+        range as u8
+    }
+}
+
 impl AccelRange {
-    // Converts accelerometer range to correction/scaling factor, see register sheet
-    pub(crate) fn sensitivity(&self) -> f32 {
+    /// Converts accelerometer range to correction/scaling factor (LSB/g), see register sheet
+    pub fn sensitivity(&self) -> f32 {
         match &self {
             AccelRange::G2 => ACCEL_SENS.0,
             AccelRange::G4 => ACCEL_SENS.1,
@@ -427,8 +1078,8 @@ impl AccelRange {
 }
 
 impl GyroRange {
-    // Converts gyro range to correction/scaling factor, see register sheet
-    pub(crate) fn sensitivity(&self) -> f32 {
+    /// Converts gyro range to correction/scaling factor (LSB/(°/s)), see register sheet
+    pub fn sensitivity(&self) -> f32 {
         match &self {
             GyroRange::D250 => GYRO_SENS.0,
             GyroRange::D500 => GYRO_SENS.1,
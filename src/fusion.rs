@@ -0,0 +1,748 @@
+//! Sensor fusion, combining accelerometer and gyro readings into an orientation estimate.
+//!
+//! All filters are `no_std`, allocation free and work on the readings returned by
+//! [`Mpu6050`](crate::Mpu6050): accel in g, gyro in rad/s.
+
+use crate::{delay_ms_long, Mpu6050, Mpu6050Error};
+use core::ops::Mul;
+use embedded_hal::blocking::{
+    delay::DelayMs,
+    i2c::{Write, WriteRead},
+};
+#[allow(unused_imports)]
+use micromath::{
+    vector::{Vector2d, Vector3d},
+    F32Ext,
+};
+
+/// Roll and pitch estimation from accelerometer readings, in rad. x: roll, y: pitch
+/// NOTE: no yaw! no magnetometer present on MPU6050
+/// https://www.nxp.com/docs/en/application-note/AN3461.pdf equation 28, 29
+pub fn acc_angles(acc: Vector3d<f32>) -> Vector2d<f32> {
+    Vector2d::<f32> {
+        // x: atan2f(acc.y, sqrtf(powf(acc.x, 2.) + powf(acc.z, 2.))),
+        // y: atan2f(-acc.x, sqrtf(powf(acc.y, 2.) + powf(acc.z, 2.)))
+        x: acc.y.atan2((acc.x.powf(2.) + acc.z.powf(2.)).sqrt()),
+        y: (-acc.x).atan2((acc.y.powf(2.) + acc.z.powf(2.)).sqrt()),
+    }
+}
+
+/// Roll/pitch definitions for [`acc_angles_with`], depending on how the chip is mounted.
+/// Axes as printed on the breakout, z up:
+/// ```text
+///        +y
+///         ^
+///         |
+///   +-----------+
+///   |  MPU6050  | --> +x
+///   +-----------+
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum RotationConvention {
+    /// x axis points forward: roll about x, pitch about y, both ±90°.
+    /// AN3461 equation 28, 29, same as [`acc_angles`]
+    #[default]
+    XForward,
+    /// chip rotated 90° counter clockwise, y axis points forward and -x to the left:
+    /// roll about y, pitch about -x, both ±90°
+    YForward,
+    /// x axis points forward, aerospace (Rxyz) sequence: roll about x in ±180°, pitch about y in ±90°.
+    /// AN3461 equation 25, 26
+    Aerospace,
+}
+
+/// Roll and pitch estimation from accelerometer readings in the given convention, in rad.
+/// x: roll, y: pitch
+pub fn acc_angles_with(acc: Vector3d<f32>, convention: RotationConvention) -> Vector2d<f32> {
+    match convention {
+        RotationConvention::XForward => acc_angles(acc),
+        // x forward formula on the rotated axes (y, -x, z)
+        RotationConvention::YForward => acc_angles(Vector3d::<f32> {
+            x: acc.y,
+            y: -acc.x,
+            z: acc.z,
+        }),
+        RotationConvention::Aerospace => Vector2d::<f32> {
+            x: acc.y.atan2(acc.z),
+            y: (-acc.x).atan2((acc.y.powf(2.) + acc.z.powf(2.)).sqrt()),
+        },
+    }
+}
+
+/// Roll and pitch, in rad or deg depending on where it comes from
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Tilt {
+    /// Rotation about the x axis
+    pub roll: f32,
+    /// Rotation about the y axis
+    pub pitch: f32,
+}
+
+impl From<Vector2d<f32>> for Tilt {
+    /// x: roll, y: pitch, as returned by [`acc_angles`]
+    fn from(angles: Vector2d<f32>) -> Self {
+        Tilt {
+            roll: angles.x,
+            pitch: angles.y,
+        }
+    }
+}
+
+/// Complementary filter for roll and pitch.
+///
+/// Integrates the gyro rates and corrects the drift with the accelerometer angles:
+/// angle = alpha * (angle + gyro * dt) + (1 - alpha) * acc_angle
+///
+/// alpha close to 1 (e.g. 0.98) trusts the gyro in the short term, while the accelerometer
+/// slowly pulls the estimate back.
+#[derive(Copy, Clone, Debug)]
+pub struct ComplementaryFilter {
+    alpha: f32,
+    angles: Vector2d<f32>,
+    initialized: bool,
+}
+
+impl ComplementaryFilter {
+    /// Creates a new filter with weight alpha (0..=1) for the gyro integration
+    pub fn new(alpha: f32) -> Self {
+        ComplementaryFilter {
+            alpha,
+            angles: Vector2d::<f32>::default(),
+            initialized: false,
+        }
+    }
+
+    /// Feeds accel (g), gyro (rad/s) and the time since the last update dt (s) into the filter.
+    /// Returns the fused roll (x) and pitch (y) in rad. The first update starts from the accel angles.
+    pub fn update(&mut self, acc: Vector3d<f32>, gyro: Vector3d<f32>, dt: f32) -> Vector2d<f32> {
+        let acc_angles = acc_angles(acc);
+
+        if !self.initialized {
+            self.angles = acc_angles;
+            self.initialized = true;
+            return self.angles;
+        }
+
+        self.angles = Vector2d::<f32> {
+            x: self.alpha * (self.angles.x + gyro.x * dt) + (1.0 - self.alpha) * acc_angles.x,
+            y: self.alpha * (self.angles.y + gyro.y * dt) + (1.0 - self.alpha) * acc_angles.y,
+        };
+        self.angles
+    }
+
+    /// Current roll (x) and pitch (y) estimate in rad
+    pub fn angles(&self) -> Vector2d<f32> {
+        self.angles
+    }
+
+    /// Resets the estimate, the next update starts from the accel angles again
+    pub fn reset(&mut self) {
+        self.angles = Vector2d::<f32>::default();
+        self.initialized = false;
+    }
+}
+
+/// Exponential moving average of accel angles, for a stable tilt without a gyro:
+/// angle = alpha * raw + (1 - alpha) * angle
+///
+/// Unlike the [`ComplementaryFilter`] it needs no dt, but it lags behind fast movements
+/// and is not immune to linear acceleration.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AngleSmoother {
+    angles: Vector2d<f32>,
+    initialized: bool,
+}
+
+impl AngleSmoother {
+    /// Creates a new smoother, the first update passes the raw angles through
+    pub fn new() -> Self {
+        AngleSmoother::default()
+    }
+
+    /// Feeds raw roll (x) and pitch (y) angles, e.g. from [`acc_angles`], returns the smoothed angles.
+    /// alpha (0..=1) is the weight of the new sample: smaller values smooth more and react slower
+    pub fn update(&mut self, raw: Vector2d<f32>, alpha: f32) -> Vector2d<f32> {
+        if !self.initialized {
+            self.angles = raw;
+            self.initialized = true;
+            return self.angles;
+        }
+
+        self.angles = Vector2d::<f32> {
+            x: alpha * raw.x + (1.0 - alpha) * self.angles.x,
+            y: alpha * raw.y + (1.0 - alpha) * self.angles.y,
+        };
+        self.angles
+    }
+
+    /// Current smoothed roll (x) and pitch (y)
+    pub fn angles(&self) -> Vector2d<f32> {
+        self.angles
+    }
+
+    /// Resets the smoother, the next update starts from the raw angles again
+    pub fn reset(&mut self) {
+        *self = AngleSmoother::default();
+    }
+}
+
+/// Roll and pitch tracking on top of a [`Mpu6050`]: owns the driver and a [`ComplementaryFilter`],
+/// each `update` waits one sample period, reads the sensor and feeds the filter with dt = period.
+/// The period follows `output_data_rate`, rounded to whole ms. Time spent outside of `update` is not
+/// accounted for
+pub struct OrientationTracker<I> {
+    mpu: Mpu6050<I>,
+    filter: ComplementaryFilter,
+    period_ms: u32,
+}
+
+impl<I, E> OrientationTracker<I>
+where
+    I: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Creates a tracker updating at the configured sample rate, alpha as in [`ComplementaryFilter::new`].
+    /// The driver is expected to be initialized, with DLPF and sample rate set
+    pub fn new(mpu: Mpu6050<I>, alpha: f32) -> Result<Self, Mpu6050Error<E>> {
+        let mut tracker = OrientationTracker {
+            mpu,
+            filter: ComplementaryFilter::new(alpha),
+            period_ms: 0,
+        };
+        tracker.refresh_period()?;
+        Ok(tracker)
+    }
+
+    /// Reads the period from `output_data_rate` again, e.g. after changing DLPF or sample rate through
+    /// `mpu_mut`
+    pub fn refresh_period(&mut self) -> Result<(), Mpu6050Error<E>> {
+        let rate = self.mpu.output_data_rate()?;
+        self.period_ms = ((1000.0 / rate).round() as u32).max(1);
+        Ok(())
+    }
+
+    /// Update period in ms
+    pub fn period_ms(&self) -> u32 {
+        self.period_ms
+    }
+
+    /// Waits one period, reads accel and gyro and returns the fused roll (x) and pitch (y) in rad
+    pub fn update<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<Vector2d<f32>, Mpu6050Error<E>> {
+        delay_ms_long(delay, self.period_ms);
+        let (acc, gyro, _) = self.mpu.get_motion6()?;
+        Ok(self.filter.update(
+            acc.as_g(),
+            gyro.as_rad_per_s(),
+            self.period_ms as f32 / 1000.0,
+        ))
+    }
+
+    /// Current roll (x) and pitch (y) estimate in rad
+    pub fn angles(&self) -> Vector2d<f32> {
+        self.filter.angles()
+    }
+
+    /// Resets the estimate, see [`ComplementaryFilter::reset`]
+    pub fn reset(&mut self) {
+        self.filter.reset();
+    }
+
+    /// Mutable access to the driver, e.g. to read the temperature between updates
+    pub fn mpu_mut(&mut self) -> &mut Mpu6050<I> {
+        &mut self.mpu
+    }
+
+    /// Destroys the tracker, returning the driver
+    pub fn release(self) -> Mpu6050<I> {
+        self.mpu
+    }
+}
+
+/// Relative heading (yaw) from integrating the gyro z rate.
+///
+/// Without a magnetometer this is not an absolute heading: it starts at 0 and drifts with the
+/// remaining gyro bias (e.g. 1°/s after a poor calibration), so it's only good for short term turn tracking.
+/// Assumes the sensor is roughly level, z pointing up
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HeadingIntegrator {
+    yaw: f32,
+}
+
+impl HeadingIntegrator {
+    /// Creates a new integrator at heading 0
+    pub fn new() -> Self {
+        HeadingIntegrator { yaw: 0.0 }
+    }
+
+    /// Integrates the gyro z rate (rad/s) over dt (s), returns the heading in rad, wrapped to [-π, π]
+    pub fn update(&mut self, gyro_z_rad: f32, dt: f32) -> f32 {
+        let mut yaw = self.yaw + gyro_z_rad * dt;
+        // rem_euclid isn't available in no_std, a single update rarely wraps more than once
+        while yaw > crate::PI {
+            yaw -= 2.0 * crate::PI;
+        }
+        while yaw < -crate::PI {
+            yaw += 2.0 * crate::PI;
+        }
+        self.yaw = yaw;
+        self.yaw
+    }
+
+    /// Current heading in rad, in [-π, π]
+    pub fn heading(&self) -> f32 {
+        self.yaw
+    }
+
+    /// Resets the heading to 0, e.g. to take the current direction as reference
+    pub fn reset(&mut self) {
+        self.yaw = 0.0;
+    }
+}
+
+/// Orientation quaternion, w + xi + yj + zk
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quaternion {
+    /// Real part
+    pub w: f32,
+    /// i component
+    pub x: f32,
+    /// j component
+    pub y: f32,
+    /// k component
+    pub z: f32,
+}
+
+impl Default for Quaternion {
+    /// Identity, no rotation
+    fn default() -> Self {
+        Quaternion {
+            w: 1.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+}
+
+impl Quaternion {
+    /// Rotation from Euler angles in rad, rotation order z-y-x (yaw, pitch, roll). Inverse of `to_euler`
+    pub fn from_euler(roll: f32, pitch: f32, yaw: f32) -> Self {
+        let (sr, cr) = ((roll * 0.5).sin(), (roll * 0.5).cos());
+        let (sp, cp) = ((pitch * 0.5).sin(), (pitch * 0.5).cos());
+        let (sy, cy) = ((yaw * 0.5).sin(), (yaw * 0.5).cos());
+        Quaternion {
+            w: cr * cp * cy + sr * sp * sy,
+            x: sr * cp * cy - cr * sp * sy,
+            y: cr * sp * cy + sr * cp * sy,
+            z: cr * cp * sy - sr * sp * cy,
+        }
+    }
+
+    /// Length, 1 for a rotation
+    pub fn norm(&self) -> f32 {
+        (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+
+    /// Scaled to unit length, identity for a zero quaternion
+    pub fn normalize(&self) -> Self {
+        let norm = self.norm();
+        if norm > 0.0 {
+            Quaternion {
+                w: self.w / norm,
+                x: self.x / norm,
+                y: self.y / norm,
+                z: self.z / norm,
+            }
+        } else {
+            Quaternion::default()
+        }
+    }
+
+    /// Inverse rotation of a unit quaternion
+    pub fn conjugate(&self) -> Self {
+        Quaternion {
+            w: self.w,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+
+    /// Expected gravity direction in the sensor frame (unit vector, in g), i.e. the accel reading of a
+    /// motionless sensor with this orientation
+    pub fn gravity(&self) -> Vector3d<f32> {
+        Vector3d::<f32> {
+            x: 2.0 * (self.x * self.z - self.w * self.y),
+            y: 2.0 * (self.w * self.x + self.y * self.z),
+            z: self.w * self.w - self.x * self.x - self.y * self.y + self.z * self.z,
+        }
+    }
+
+    /// Euler angles (roll, pitch, yaw) in rad, rotation order z-y-x (yaw, pitch, roll)
+    pub fn to_euler(&self) -> (f32, f32, f32) {
+        let roll = (2.0 * (self.w * self.x + self.y * self.z))
+            .atan2(1.0 - 2.0 * (self.x * self.x + self.y * self.y));
+        // clamp, rounding errors can push the argument past ±1 at ±90° pitch
+        let pitch = (2.0 * (self.w * self.y - self.z * self.x))
+            .clamp(-1.0, 1.0)
+            .asin();
+        let yaw = (2.0 * (self.w * self.z + self.x * self.y))
+            .atan2(1.0 - 2.0 * (self.y * self.y + self.z * self.z));
+        (roll, pitch, yaw)
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    /// Hamilton product, `a * b` applies rotation b first, then a
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        Quaternion {
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        }
+    }
+}
+
+/// Acceleration with gravity removed, in g. acc in g, orientation e.g. from [`Madgwick`]
+pub fn linear_acc(acc: Vector3d<f32>, orientation: &Quaternion) -> Vector3d<f32> {
+    acc - orientation.gravity()
+}
+
+/// Madgwick AHRS filter, IMU version (accel + gyro, no magnetometer).
+///
+/// Gyro rates are integrated into a quaternion, a gradient descent step with gain beta corrects it
+/// towards the measured gravity direction. Yaw is not corrected and drifts.
+/// https://x-io.co.uk/open-source-imu-and-ahrs-algorithms/
+#[derive(Copy, Clone, Debug)]
+pub struct Madgwick {
+    sample_period: f32,
+    beta: f32,
+    q: Quaternion,
+}
+
+impl Madgwick {
+    /// Creates a new filter for updates at sample_freq (Hz). beta is the gradient descent gain,
+    /// higher values converge faster on the accelerometer, but let more accel noise through (e.g. 0.1)
+    pub fn new(sample_freq: f32, beta: f32) -> Self {
+        Madgwick {
+            sample_period: 1.0 / sample_freq,
+            beta,
+            q: Quaternion::default(),
+        }
+    }
+
+    /// Feeds gyro (rad/s) and accel (any unit, it is normalized) into the filter, returns the updated orientation
+    pub fn update_imu(&mut self, gyro: Vector3d<f32>, acc: Vector3d<f32>) -> Quaternion {
+        let Quaternion {
+            w: q0,
+            x: q1,
+            y: q2,
+            z: q3,
+        } = self.q;
+
+        // rate of change of quaternion from gyro
+        let mut q_dot0 = 0.5 * (-q1 * gyro.x - q2 * gyro.y - q3 * gyro.z);
+        let mut q_dot1 = 0.5 * (q0 * gyro.x + q2 * gyro.z - q3 * gyro.y);
+        let mut q_dot2 = 0.5 * (q0 * gyro.y - q1 * gyro.z + q3 * gyro.x);
+        let mut q_dot3 = 0.5 * (q0 * gyro.z + q1 * gyro.y - q2 * gyro.x);
+
+        // feedback only with a valid accel measurement (avoids NaN in normalisation)
+        let acc_norm = (acc.x * acc.x + acc.y * acc.y + acc.z * acc.z).sqrt();
+        if acc_norm > 0.0 {
+            let ax = acc.x / acc_norm;
+            let ay = acc.y / acc_norm;
+            let az = acc.z / acc_norm;
+
+            let q0q0 = q0 * q0;
+            let q1q1 = q1 * q1;
+            let q2q2 = q2 * q2;
+            let q3q3 = q3 * q3;
+
+            // gradient descent corrective step
+            let s0 = 4.0 * q0 * q2q2 + 2.0 * q2 * ax + 4.0 * q0 * q1q1 - 2.0 * q1 * ay;
+            let s1 = 4.0 * q1 * q3q3 - 2.0 * q3 * ax + 4.0 * q0q0 * q1 - 2.0 * q0 * ay - 4.0 * q1
+                + 8.0 * q1 * q1q1
+                + 8.0 * q1 * q2q2
+                + 4.0 * q1 * az;
+            let s2 = 4.0 * q0q0 * q2 + 2.0 * q0 * ax + 4.0 * q2 * q3q3 - 2.0 * q3 * ay - 4.0 * q2
+                + 8.0 * q2 * q1q1
+                + 8.0 * q2 * q2q2
+                + 4.0 * q2 * az;
+            let s3 = 4.0 * q1q1 * q3 - 2.0 * q1 * ax + 4.0 * q2q2 * q3 - 2.0 * q2 * ay;
+            let s_norm = (s0 * s0 + s1 * s1 + s2 * s2 + s3 * s3).sqrt();
+
+            // already aligned with gravity, if the gradient vanishes
+            if s_norm > 0.0 {
+                q_dot0 -= self.beta * s0 / s_norm;
+                q_dot1 -= self.beta * s1 / s_norm;
+                q_dot2 -= self.beta * s2 / s_norm;
+                q_dot3 -= self.beta * s3 / s_norm;
+            }
+        }
+
+        // integrate and normalise
+        self.q = Quaternion {
+            w: q0 + q_dot0 * self.sample_period,
+            x: q1 + q_dot1 * self.sample_period,
+            y: q2 + q_dot2 * self.sample_period,
+            z: q3 + q_dot3 * self.sample_period,
+        }
+        .normalize();
+        self.q
+    }
+
+    /// Current orientation
+    pub fn quaternion(&self) -> Quaternion {
+        self.q
+    }
+
+    /// Current orientation as Euler angles (roll, pitch, yaw) in rad
+    pub fn euler(&self) -> (f32, f32, f32) {
+        self.q.to_euler()
+    }
+
+    /// Resets the orientation to identity
+    pub fn reset(&mut self) {
+        self.q = Quaternion::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::vec;
+
+    use embedded_hal_mock::delay::MockNoop;
+    use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    #[test]
+    fn acc_angles_with_test() {
+        // tilted about x by 30°
+        let acc = Vector3d::<f32> {
+            x: 0.0,
+            y: 0.5,
+            z: 0.866,
+        };
+        let angles = acc_angles_with(acc, RotationConvention::default());
+        assert_eq!(angles, acc_angles(acc));
+        assert!((angles.x - crate::PI / 6.0).abs() < 1e-3 && angles.y.abs() < 1e-6);
+
+        // same tilt with the chip rotated 90°: a pitch about the rotated axes
+        let angles = acc_angles_with(acc, RotationConvention::YForward);
+        assert!(angles.x.abs() < 1e-6 && (angles.y + crate::PI / 6.0).abs() < 1e-3);
+
+        // upside down, only the aerospace convention covers roll beyond 90°
+        let acc = Vector3d::<f32> {
+            x: 0.0,
+            y: 0.5,
+            z: -0.866,
+        };
+        let angles = acc_angles_with(acc, RotationConvention::Aerospace);
+        assert!((angles.x - 5.0 * crate::PI / 6.0).abs() < 1e-3 && angles.y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn complementary_filter_test() {
+        let mut filter = ComplementaryFilter::new(0.98);
+        let zero = Vector3d::<f32>::default();
+
+        // level and stationary
+        let level = Vector3d::<f32> {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+        };
+        let angles = filter.update(level, zero, 0.01);
+        assert!(angles.x.abs() < 1e-6 && angles.y.abs() < 1e-6);
+
+        // tilted about x by 30°, the estimate converges towards the accel angle
+        let tilted = Vector3d::<f32> {
+            x: 0.0,
+            y: 0.5,
+            z: 0.866,
+        };
+        for _ in 0..500 {
+            filter.update(tilted, zero, 0.01);
+        }
+        assert!((filter.angles().x - crate::PI / 6.0).abs() < 0.01);
+        assert!(filter.angles().y.abs() < 1e-3);
+    }
+
+    #[test]
+    fn angle_smoother_test() {
+        let mut smoother = AngleSmoother::new();
+        let first = smoother.update(Vector2d { x: 0.2, y: -0.1 }, 0.1);
+        assert_eq!(first, Vector2d { x: 0.2, y: -0.1 });
+
+        // jitter of ±0.05 around (0.1, 0.3)
+        for i in 0..200 {
+            let noise = if i % 2 == 0 { 0.05 } else { -0.05 };
+            smoother.update(
+                Vector2d {
+                    x: 0.1 + noise,
+                    y: 0.3 - noise,
+                },
+                0.1,
+            );
+        }
+        let angles = smoother.angles();
+        assert!((angles.x - 0.1).abs() < 0.01);
+        assert!((angles.y - 0.3).abs() < 0.01);
+
+        smoother.reset();
+        assert_eq!(
+            smoother.update(Vector2d { x: 1.0, y: 1.0 }, 0.1),
+            Vector2d { x: 1.0, y: 1.0 }
+        );
+    }
+
+    #[test]
+    fn madgwick_test() {
+        let mut filter = Madgwick::new(100.0, 0.5);
+        let zero = Vector3d::<f32>::default();
+
+        // tilted about x by 30°, stationary: converges to the gravity aligned orientation
+        let tilted = Vector3d::<f32> {
+            x: 0.0,
+            y: 0.5,
+            z: 0.866,
+        };
+        for _ in 0..2000 {
+            filter.update_imu(zero, tilted);
+        }
+        let (roll, pitch, yaw) = filter.euler();
+        assert!((roll - crate::PI / 6.0).abs() < 0.01);
+        assert!(pitch.abs() < 0.01);
+        assert!(yaw.abs() < 0.01);
+    }
+
+    #[test]
+    fn quaternion_test() {
+        // Euler round trip, including close to the ±90° pitch singularity
+        for &(roll, pitch, yaw) in &[
+            (0.3_f32, -0.5_f32, 1.2_f32),
+            (-2.0, 1.5, -3.0),
+            (0.7, -1.56, 0.4),
+        ] {
+            let q = Quaternion::from_euler(roll, pitch, yaw);
+            assert!((q.norm() - 1.0).abs() < 1e-5);
+            let (r, p, y) = q.to_euler();
+            assert!((r - roll).abs() < 0.01 && (p - pitch).abs() < 0.01 && (y - yaw).abs() < 0.01);
+        }
+
+        // rotations about the same axis add up
+        let q = Quaternion::from_euler(0.0, 0.0, 0.5) * Quaternion::from_euler(0.0, 0.0, 0.25);
+        let (r, p, y) = q.to_euler();
+        assert!(r.abs() < 1e-5 && p.abs() < 1e-5 && (y - 0.75).abs() < 1e-5);
+        let identity = q * q.conjugate();
+        assert!((identity.w - 1.0).abs() < 1e-5 && identity.x.abs() < 1e-5);
+
+        let q = Quaternion {
+            w: 2.0,
+            x: 0.0,
+            y: 0.0,
+            z: 2.0,
+        }
+        .normalize();
+        assert!((q.norm() - 1.0).abs() < 1e-6);
+        assert_eq!(
+            Quaternion {
+                w: 0.0,
+                x: 0.0,
+                y: 0.0,
+                z: 0.0
+            }
+            .normalize(),
+            Quaternion::default()
+        );
+    }
+
+    #[test]
+    fn orientation_tracker_test() {
+        // stationary, tilted about x by 30°: 0.5g on y, 0.866g on z, no rotation
+        let read = I2cTransaction::write_read(
+            0x68,
+            vec![crate::device::ACC_REGX_H],
+            vec![0x00, 0x00, 0x20, 0x00, 0x37, 0x6D, 0, 0, 0, 0, 0, 0, 0, 0],
+        );
+        // 1 kHz / (1 + 9): 10 ms period
+        let mut expectations = vec![
+            I2cTransaction::write_read(0x68, vec![crate::device::CONFIG::ADDR], vec![0x03]),
+            I2cTransaction::write_read(0x68, vec![crate::device::SMPLRT_DIV], vec![9]),
+        ];
+        expectations.extend(vec![read.clone(); 100]);
+        let mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut tracker = OrientationTracker::new(mpu, 0.98).unwrap();
+        assert_eq!(tracker.period_ms(), 10);
+        let mut delay = MockNoop::new();
+        for _ in 0..100 {
+            tracker.update(&mut delay).unwrap();
+        }
+        let angles = tracker.angles();
+        assert!((angles.x - crate::PI / 6.0).abs() < 0.01 && angles.y.abs() < 0.01);
+        tracker.release().release().done();
+
+        // 1 kHz / 256: 256 ms, longer than a single DelayMs<u8> call
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![crate::device::CONFIG::ADDR], vec![0x03]),
+            I2cTransaction::write_read(0x68, vec![crate::device::SMPLRT_DIV], vec![255]),
+            read,
+        ];
+        let mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut tracker = OrientationTracker::new(mpu, 0.98).unwrap();
+        let mut delay = crate::tests::DelayTotal(0);
+        tracker.update(&mut delay).unwrap();
+        assert_eq!(delay.0, 256);
+        tracker.release().release().done();
+    }
+
+    #[test]
+    fn heading_integrator_test() {
+        let mut heading = HeadingIntegrator::new();
+        // 90°/s for 1s
+        for _ in 0..100 {
+            heading.update(crate::PI / 2.0, 0.01);
+        }
+        assert!((heading.heading() - crate::PI / 2.0).abs() < 1e-4);
+
+        // another 180° wraps around to -90°
+        for _ in 0..200 {
+            heading.update(crate::PI / 2.0, 0.01);
+        }
+        assert!((heading.heading() + crate::PI / 2.0).abs() < 1e-4);
+
+        // turning back past -180°
+        let yaw = heading.update(-crate::PI, 1.0);
+        assert!((yaw - crate::PI / 2.0).abs() < 1e-4);
+        heading.reset();
+        assert_eq!(heading.heading(), 0.0);
+    }
+
+    #[test]
+    fn linear_acc_test() {
+        // motionless, tilted about x by 30° and y by -20°
+        let (sr, cr) = (0.5_f32, 0.866_f32);
+        let (sp, cp) = (
+            (-20.0_f32).to_radians().sin(),
+            (-20.0_f32).to_radians().cos(),
+        );
+        let acc = Vector3d::<f32> {
+            x: -sp,
+            y: cp * sr,
+            z: cp * cr,
+        };
+
+        let mut filter = Madgwick::new(100.0, 0.5);
+        for _ in 0..2000 {
+            filter.update_imu(Vector3d::<f32>::default(), acc);
+        }
+        let linear = linear_acc(acc, &filter.quaternion());
+        assert!(linear.x.abs() < 0.01 && linear.y.abs() < 0.01 && linear.z.abs() < 0.01);
+    }
+}
@@ -31,7 +31,7 @@
 //!
 //!         // get sensor temp
 //!         let temp = mpu.get_temp()?;
-//!         printlnasd!("temp: {:?}c", temp);
+//!         println!("temp: {:?}c", temp);
 //!
 //!         // get gyro data, scaled with sensitivity
 //!         let gyro = mpu.get_gyro()?;
@@ -46,23 +46,29 @@
 
 #![no_std]
 
-mod bits;
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod bits;
 pub mod device;
+pub mod fusion;
+pub mod mux;
 
-extern crate alloc;
-
+#[cfg(feature = "async")]
+pub use crate::asynch::Mpu6050Async;
 use crate::device::*;
+use core::ops::Deref;
+#[cfg(feature = "defmt")]
+use defmt::{debug, info, Format};
 use embedded_hal::{
     blocking::delay::DelayMs,
     blocking::i2c::{Write, WriteRead},
+    digital::v2::InputPin,
 };
 #[allow(unused_imports)]
 use micromath::{
     vector::{Vector2d, Vector3d},
     F32Ext,
 };
-#[cfg(feature = "defmt")]
-use defmt::{Format, info, debug};
 
 /// PI, f32
 pub const PI: f32 = core::f32::consts::PI;
@@ -70,6 +76,9 @@ pub const PI: f32 = core::f32::consts::PI;
 /// PI / 180, for conversion to radians
 pub const PI_180: f32 = PI / 180.0;
 
+/// Standard gravity in m/s², for conversion of g readings to SI units
+pub const STANDARD_GRAVITY: f32 = 9.80665;
+
 /// All possible errors in this crate
 #[derive(Debug)]
 pub enum Mpu6050Error<E> {
@@ -78,6 +87,65 @@ pub enum Mpu6050Error<E> {
 
     /// Invalid chip ID was read
     InvalidChipId(u8),
+
+    /// Requested sample rate (Hz) can't be achieved with the current DLPF config
+    InvalidSampleRate(u16),
+
+    /// Auxiliary I2C slave slot out of range (0 - 3)
+    InvalidSlaveSlot(u8),
+
+    /// I2C mux channel out of range (0 - 7), see [`mux::MuxChannel`]
+    InvalidMuxChannel(u8),
+
+    /// Temperature sensor is disabled (PWR_MGMT_1, TEMP_DIS), e.g. by the low power accel mode
+    TemperatureDisabled,
+
+    /// I2C address is not a valid 7 bit address, or reserved (0x00 - 0x07, 0x78 - 0x7F)
+    InvalidSlaveAddr(u8),
+
+    /// Calibration didn't reach its target, carries the final mean per axis (raw counts)
+    CalibrationFailed(Vector3d<f32>),
+
+    /// Averaged reads need at least one sample
+    InvalidSampleCount,
+
+    /// Caller buffer is shorter than the data to read, carries the required length
+    BufferTooSmall(usize),
+
+    /// No device with a known chip ID answered at any of the probed addresses
+    DeviceNotFound,
+
+    /// Reading a GPIO pin failed, e.g. the one wired to the INT pin
+    Pin,
+
+    /// Sensor reading while the device sleeps, see `set_wake_check`
+    Asleep,
+
+    /// Requested setting or reading conflicts with the current device configuration
+    InvalidConfig,
+
+    /// No response to the first access during init, e.g. sensor not connected or wrong address.
+    /// Carries the address tried
+    DeviceNotResponding(u8),
+
+    /// Register read back a different value than written, see `write_byte_verified`
+    WriteVerifyFailed {
+        /// Register written
+        reg: u8,
+        /// Value written
+        expected: u8,
+        /// Value read back
+        got: u8,
+    },
+
+    /// Block write longer than `MAX_BLOCK_WRITE` bytes, carries the requested length
+    WriteTooLong(usize),
+
+    /// Magnetometer measurement overflowed (ST2, HOFL), the field exceeds the measurement range
+    MagOverflow,
+
+    /// Calibration parameters can't work, e.g. max_steps of 0
+    InvalidCalibrationParams,
 }
 
 #[cfg(feature = "defmt")]
@@ -89,16 +157,294 @@ where
         match self {
             Mpu6050Error::I2c(e) => defmt::write!(f, "I2c error: {}", e),
             Mpu6050Error::InvalidChipId(id) => defmt::write!(f, "Invalid chip ID: {}", id),
+            Mpu6050Error::InvalidSampleRate(hz) => {
+                defmt::write!(f, "Invalid sample rate: {} Hz", hz)
+            }
+            Mpu6050Error::InvalidSlaveSlot(slot) => {
+                defmt::write!(f, "Invalid I2C slave slot: {}", slot)
+            }
+            Mpu6050Error::InvalidMuxChannel(channel) => {
+                defmt::write!(f, "Invalid I2C mux channel: {}", channel)
+            }
+            Mpu6050Error::TemperatureDisabled => defmt::write!(f, "Temperature sensor disabled"),
+            Mpu6050Error::InvalidSlaveAddr(addr) => {
+                defmt::write!(f, "Invalid I2C address: 0x{:X}", addr)
+            }
+            Mpu6050Error::CalibrationFailed(mean) => {
+                defmt::write!(
+                    f,
+                    "Calibration failed, mean: x = {}, y = {}, z = {}",
+                    mean.x,
+                    mean.y,
+                    mean.z
+                )
+            }
+            Mpu6050Error::InvalidSampleCount => defmt::write!(f, "Invalid sample count: 0"),
+            Mpu6050Error::BufferTooSmall(len) => {
+                defmt::write!(f, "Buffer too small, {} bytes required", len)
+            }
+            Mpu6050Error::DeviceNotFound => defmt::write!(f, "Device not found"),
+            Mpu6050Error::Pin => defmt::write!(f, "GPIO pin error"),
+            Mpu6050Error::Asleep => defmt::write!(f, "Device asleep"),
+            Mpu6050Error::InvalidConfig => defmt::write!(f, "Invalid config"),
+            Mpu6050Error::DeviceNotResponding(addr) => {
+                defmt::write!(f, "Device not responding at 0x{:X}", addr)
+            }
+            Mpu6050Error::WriteVerifyFailed { reg, expected, got } => {
+                defmt::write!(
+                    f,
+                    "Write verify failed at 0x{:X}: wrote 0x{:X}, read 0x{:X}",
+                    reg,
+                    expected,
+                    got
+                )
+            }
+            Mpu6050Error::WriteTooLong(len) => {
+                defmt::write!(f, "Block write too long: {} bytes", len)
+            }
+            Mpu6050Error::MagOverflow => defmt::write!(f, "Magnetometer overflow"),
+            Mpu6050Error::InvalidCalibrationParams => {
+                defmt::write!(f, "Invalid calibration params")
+            }
+        }
+    }
+}
+
+/// Accelerometer reading, returned by [`Mpu6050::get_acc`]. Stored in g, derefs to the g vector
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Acceleration(pub Vector3d<f32>);
+
+impl Acceleration {
+    /// Acceleration in g
+    pub fn as_g(&self) -> Vector3d<f32> {
+        self.0
+    }
+
+    /// Acceleration in m/s²
+    pub fn as_ms2(&self) -> Vector3d<f32> {
+        let mut acc = self.0;
+        acc *= STANDARD_GRAVITY;
+        acc
+    }
+}
+
+impl Deref for Acceleration {
+    type Target = Vector3d<f32>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Gyro reading, returned by [`Mpu6050::get_gyro`]. Stored in rad/s, derefs to the rad/s vector
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AngularVelocity(pub Vector3d<f32>);
+
+impl AngularVelocity {
+    /// Angular velocity in rad/s
+    pub fn as_rad_per_s(&self) -> Vector3d<f32> {
+        self.0
+    }
+
+    /// Angular velocity in deg/s
+    pub fn as_deg_per_s(&self) -> Vector3d<f32> {
+        let mut gyro = self.0;
+        gyro *= 1.0 / PI_180;
+        gyro
+    }
+}
+
+impl Deref for AngularVelocity {
+    type Target = Vector3d<f32>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Accel (g), gyro (rad/s) and temperature (°C) from a single burst read, see [`Mpu6050::get_measurement`].
+/// Plain arrays instead of vectors, so it can be serialized with the `serde` feature
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measurement {
+    /// Accelerometer x, y, z in g
+    pub acc: [f32; 3],
+    /// Gyro x, y, z in rad/s
+    pub gyro: [f32; 3],
+    /// Temperature in °C
+    pub temp: f32,
+}
+
+/// Monotonic time source for [`Mpu6050::get_measurement_timed`], e.g. a wrapper around a hardware timer
+pub trait Clock {
+    /// Current time in µs, must not decrease
+    fn now_micros(&self) -> u64;
+}
+
+/// Measurement tagged with the time it was taken, returned by [`Mpu6050::get_measurement_timed`]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedMeasurement {
+    /// Accel, gyro and temperature
+    pub measurement: Measurement,
+    /// [`Clock::now_micros`] right before the read
+    pub timestamp_us: u64,
+}
+
+impl TimedMeasurement {
+    /// Time in s since prev was taken, e.g. as dt for the fusion filters
+    pub fn dt_since(&self, prev: &TimedMeasurement) -> f32 {
+        self.timestamp_us.saturating_sub(prev.timestamp_us) as f32 / 1_000_000.0
+    }
+}
+
+/// Compact measurement for bandwidth limited links, returned by [`Mpu6050::get_measurement_fixed`]: raw
+/// register values plus the range codes (16 bytes), converted with [`MeasurementFixed::to_measurement`]
+/// on the receiving side
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeasurementFixed {
+    /// Raw accelerometer x, y, z
+    pub acc: [i16; 3],
+    /// Raw gyro x, y, z
+    pub gyro: [i16; 3],
+    /// Raw temperature
+    pub temp: i16,
+    /// Accel range code, see `AccelRange`
+    pub accel_range: u8,
+    /// Gyro range code, see `GyroRange`
+    pub gyro_range: u8,
+}
+
+impl MeasurementFixed {
+    /// Scales to accel (g), gyro (rad/s) and temperature (°C), with the datasheet temperature conversion
+    pub fn to_measurement(&self) -> Measurement {
+        let accel_range = AccelRange::from(self.accel_range);
+        let gyro_range = GyroRange::from(self.gyro_range);
+        Measurement {
+            acc: self.acc.map(|raw| scale_accel(raw, accel_range)),
+            gyro: self.gyro.map(|raw| scale_gyro(raw, gyro_range) * PI_180),
+            temp: self.temp as f32 / TEMP_SENSITIVITY + TEMP_OFFSET,
         }
     }
 }
 
+/// Single sample decoded from FIFO bytes by [`Mpu6050::parse_fifo_sample`], None for sources not in the layout
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FifoSample {
+    /// Accelerometer reading
+    pub acc: Option<Acceleration>,
+    /// Temperature in °C
+    pub temp: Option<f32>,
+    /// Gyro reading
+    pub gyro: Option<AngularVelocity>,
+}
+
+/// Axes at the full scale limit (±32767 counts), returned by [`Mpu6050::is_saturated`]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SaturationFlags {
+    /// Accelerometer x, y, z saturated
+    pub acc: [bool; 3],
+    /// Gyro x, y, z saturated
+    pub gyro: [bool; 3],
+}
+
+impl SaturationFlags {
+    /// Whether any axis is saturated
+    pub fn any(&self) -> bool {
+        self.acc.iter().chain(self.gyro.iter()).any(|&s| s)
+    }
+}
+
+/// Result of [`Mpu6050::self_test`]: change of the self test response from the factory trim in %, per axis
+#[derive(Copy, Clone, Debug)]
+pub struct SelfTestResult {
+    /// Accelerometer deviation x, y, z in %
+    pub accel_deviation: Vector3d<f32>,
+    /// Gyro deviation x, y, z in %
+    pub gyro_deviation: Vector3d<f32>,
+    /// All deviations within ±14%
+    pub passed: bool,
+}
+
+/// All offset registers, read by [`Mpu6050::get_all_offsets`]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AllOffsets {
+    /// Gyro offsets x, y, z (XG/YG/ZG_OFFS_USR)
+    pub gyro: Vector3d<i32>,
+    /// Accel offsets x, y, z (XA/YA/ZA_OFFS_USR), +-16g format with the reserved bit 0 masked out
+    pub accel: Vector3d<i32>,
+}
+
+#[cfg(feature = "defmt")]
+impl Format for Measurement {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "acc({}, {}, {}) gyro({}, {}, {}) temp {}",
+            self.acc[0],
+            self.acc[1],
+            self.acc[2],
+            self.gyro[0],
+            self.gyro[1],
+            self.gyro[2],
+            self.temp
+        );
+    }
+}
+
+/// Raw snapshot of the registers SMPLRT_DIV (0x19) through PWR_MGMT_2 (0x6C), see [`Mpu6050::dump_registers`]
+#[derive(Copy, Clone, Debug)]
+pub struct RegisterDump(pub [u8; REGISTER_DUMP_LEN]);
+
+impl RegisterDump {
+    /// Value of register reg, None if reg isn't part of the dump
+    pub fn reg(&self, reg: u8) -> Option<u8> {
+        let idx = reg.checked_sub(REGISTER_DUMP_START)? as usize;
+        self.0.get(idx).copied()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl Format for RegisterDump {
+    fn format(&self, f: defmt::Formatter) {
+        let reg = |addr: u8| self.0[(addr - REGISTER_DUMP_START) as usize];
+        let dlpf = DLPF::from(bits::get_bits(
+            reg(CONFIG::ADDR),
+            CONFIG::DLPF_CFG.bit,
+            CONFIG::DLPF_CFG.length,
+        ));
+        let div = reg(SMPLRT_DIV);
+        let pwr = reg(PWR_MGMT_1::ADDR);
+        defmt::write!(
+            f,
+            "RegisterDump< accel FS_SEL: {}, gyro FS_SEL: {}, DLPF_CFG: {}, SMPLRT_DIV: {} ({} Hz), sleep: {}, cycle: {}, CLKSEL: {}, PWR_MGMT_2: 0x{:X}, raw: {:X} >",
+            bits::get_bits(reg(ACCEL_CONFIG::ADDR), ACCEL_CONFIG::FS_SEL.bit, ACCEL_CONFIG::FS_SEL.length),
+            bits::get_bits(reg(GYRO_CONFIG::ADDR), GYRO_CONFIG::FS_SEL.bit, GYRO_CONFIG::FS_SEL.length),
+            dlpf as u8,
+            div,
+            dlpf.gyro_output_rate() / (1 + div as u16),
+            bits::get_bit(pwr, PWR_MGMT_1::SLEEP),
+            bits::get_bit(pwr, PWR_MGMT_1::CYCLE),
+            bits::get_bits(pwr, PWR_MGMT_1::CLKSEL.bit, PWR_MGMT_1::CLKSEL.length),
+            reg(PWR_MGMT_2::ADDR),
+            self.0
+        );
+    }
+}
+
 /// Handles all operations on/with Mpu6050
 pub struct Mpu6050<I> {
     i2c: I,
     slave_addr: u8,
-    acc_sensitivity: f32,
-    gyro_sensitivity: f32,
+    conv: Conversion,
+    model: Option<DeviceModel>,
+    int_active_low: bool,
+    mag_scale: Option<Vector3d<f32>>,
+    awake: bool,
+    wake_check: bool,
+    expected_chip_id: Option<u8>,
+    pending_int: u8,
 }
 
 #[cfg(feature = "defmt")]
@@ -111,531 +457,4730 @@ where
             f,
             "Mpu6050< addr: 0x{:X}, acc_sensitivity: {}, gyro_sensitivity: {} >",
             self.slave_addr,
-            self.acc_sensitivity,
-            self.gyro_sensitivity
+            self.conv.acc_sensitivity,
+            self.conv.gyro_sensitivity
         );
     }
 }
 
-impl<I, E> Mpu6050<I>
-where
-    I: Write<Error = E> + WriteRead<Error = E>,
-{
-    /// Side effect free constructor with default sensitivies, no calibration
-    pub fn new(i2c: I) -> Self {
-        Mpu6050 {
-            i2c,
-            slave_addr: DEFAULT_SLAVE_ADDR,
-            acc_sensitivity: ACCEL_SENS.0,
-            gyro_sensitivity: GYRO_SENS.0,
-        }
+/// Scales a raw accelerometer count (e.g. from `get_acc_raw`) to g for the given range,
+/// as `get_acc` does. Useful to convert logged raw data later on, without a device
+pub fn scale_accel(raw: i16, range: AccelRange) -> f32 {
+    raw as f32 / range.sensitivity()
+}
+
+/// Scales a raw gyro count (e.g. from `get_gyro_raw`) to deg/s for the given range, as `get_gyro_deg` does
+pub fn scale_gyro(raw: i16, range: GyroRange) -> f32 {
+    raw as f32 / range.sensitivity()
+}
+
+/// Converts 2 bytes number in 2 compliment (big endian, as in all sensor registers)
+pub(crate) fn read_word_2c(byte: &[u8]) -> i32 {
+    i16::from_be_bytes([byte[0], byte[1]]) as i32
+}
+
+/// Converts 6 bytes (x, y, z big endian words) into a raw vector
+pub(crate) fn read_vec_i16(bytes: &[u8]) -> Vector3d<i16> {
+    Vector3d::<i16> {
+        x: i16::from_be_bytes([bytes[0], bytes[1]]),
+        y: i16::from_be_bytes([bytes[2], bytes[3]]),
+        z: i16::from_be_bytes([bytes[4], bytes[5]]),
     }
+}
 
-    /// custom sensitivity
-    pub fn new_with_sens(i2c: I, arange: AccelRange, grange: GyroRange) -> Self {
-        Mpu6050 {
-            i2c,
-            slave_addr: DEFAULT_SLAVE_ADDR,
+/// Maximum payload of `Mpu6050::write_bytes`, in bytes
+pub const MAX_BLOCK_WRITE: usize = 16;
+
+/// Whether peak (raw counts at the widest range) stays within the headroom of a range with `scale` times
+/// the widest range's sensitivity
+pub(crate) fn fits_range(peak: i32, scale: f32) -> bool {
+    peak as f32 * scale < AUTO_RANGE_HEADROOM * i16::MAX as f32
+}
+
+/// Conversion state shared by [`Mpu6050`] and the async driver: ranges and sensitivities, the fine tune
+/// offsets from calibration, the gyro temperature model and the temperature conversion. Raw readings of
+/// both drivers are scaled through it only, so they can't diverge
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Conversion {
+    pub(crate) acc_range: AccelRange,
+    pub(crate) gyro_range: GyroRange,
+    pub(crate) acc_sensitivity: f32,
+    pub(crate) gyro_sensitivity: f32,
+    pub(crate) acc_fine_tune_offsets: Vector3d<i32>,
+    pub(crate) gyro_fine_tune_offsets: Vector3d<i32>,
+    pub(crate) gyro_temp_model: Option<GyroTempModel>,
+    pub(crate) temp_sensitivity: f32,
+    pub(crate) temp_offset: f32,
+    pub(crate) temp_enabled: bool,
+}
+
+impl Conversion {
+    /// Defaults for the given ranges, no calibration
+    pub(crate) fn new(arange: AccelRange, grange: GyroRange) -> Self {
+        Conversion {
+            acc_range: arange,
+            gyro_range: grange,
             acc_sensitivity: arange.sensitivity(),
             gyro_sensitivity: grange.sensitivity(),
+            acc_fine_tune_offsets: Vector3d::<i32>::default(),
+            gyro_fine_tune_offsets: Vector3d::<i32>::default(),
+            gyro_temp_model: None,
+            temp_sensitivity: TEMP_SENSITIVITY,
+            temp_offset: TEMP_OFFSET,
+            temp_enabled: true,
         }
     }
 
-    /// Same as `new`, but the chip address can be specified (e.g. 0x69, if the A0 pin is pulled up)
-    pub fn new_with_addr(i2c: I, slave_addr: u8) -> Self {
-        Mpu6050 {
-            i2c,
-            slave_addr,
-            acc_sensitivity: ACCEL_SENS.0,
-            gyro_sensitivity: GYRO_SENS.0,
+    /// Accel counts from a raw reading, with the fine tune offsets from calibration applied
+    pub(crate) fn acc_counts(&self, raw: Vector3d<i16>) -> Vector3d<i32> {
+        Vector3d::<i32> {
+            x: raw.x as i32,
+            y: raw.y as i32,
+            z: raw.z as i32,
+        } + self.acc_fine_tune_offsets
+    }
+
+    /// Accel counts scaled to g
+    pub(crate) fn acc_scale(&self, counts: Vector3d<i32>) -> Acceleration {
+        let mut acc = Vector3d::<f32> {
+            x: counts.x as f32,
+            y: counts.y as f32,
+            z: counts.z as f32,
+        };
+        acc *= 1.0 / self.acc_sensitivity;
+        Acceleration(acc)
+    }
+
+    /// Same as `acc_counts`, scaled to g
+    pub(crate) fn acc_from_raw(&self, raw: Vector3d<i16>) -> Acceleration {
+        self.acc_scale(self.acc_counts(raw))
+    }
+
+    /// Whether gyro reads need the temperature of the same sample, for the gyro temperature model
+    pub(crate) fn gyro_needs_temp(&self) -> bool {
+        self.gyro_temp_model.is_some() && self.temp_enabled
+    }
+
+    /// Temperature for the gyro temperature model, None while the sensor is disabled, as TEMP_OUT is stale then
+    pub(crate) fn gyro_model_temp(&self, temp: f32) -> Option<f32> {
+        if self.temp_enabled {
+            Some(temp)
+        } else {
+            None
         }
     }
 
-    /// Combination of `new_with_sens` and `new_with_addr`
-    pub fn new_with_addr_and_sens(
-        i2c: I,
-        slave_addr: u8,
-        arange: AccelRange,
-        grange: GyroRange,
-    ) -> Self {
-        Mpu6050 {
-            i2c,
-            slave_addr,
-            acc_sensitivity: arange.sensitivity(),
-            gyro_sensitivity: grange.sensitivity(),
+    /// Gyro counts from a raw reading, with the fine tune offsets from calibration and, given the
+    /// temperature, the gyro temperature model applied. Shared by all gyro reads
+    pub(crate) fn gyro_counts(&self, raw: Vector3d<i16>, temp: Option<f32>) -> Vector3d<i32> {
+        let mut gyro = Vector3d::<i32> {
+            x: raw.x as i32,
+            y: raw.y as i32,
+            z: raw.z as i32,
+        } + self.gyro_fine_tune_offsets;
+        if let (Some(model), Some(temp)) = (self.gyro_temp_model, temp) {
+            gyro -= model.bias_i32(temp);
         }
+        gyro
     }
 
-    /// Wakes MPU6050 with all sensors enabled (default)
-    fn wake<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
-        // MPU6050 has sleep enabled by default -> set bit 0 to wake
-        // Set clock source to be PLL with x-axis gyroscope reference, bits 2:0 = 001 (See Register Map )
-        self.write_byte(PWR_MGMT_1::ADDR, 0x01)?;
-        delay.delay_ms(100u8);
-        Ok(())
+    /// Gyro counts scaled to rad/s
+    pub(crate) fn gyro_scale(&self, counts: Vector3d<i32>) -> AngularVelocity {
+        let mut gyro = Vector3d::<f32> {
+            x: counts.x as f32,
+            y: counts.y as f32,
+            z: counts.z as f32,
+        };
+        gyro *= PI_180 / self.gyro_sensitivity;
+        AngularVelocity(gyro)
     }
 
-    /// From Register map:
-    /// "An  internal  8MHz  oscillator,  gyroscope based  clock,or  external  sources  can  be
-    /// selected  as the MPU-60X0 clock source.
-    /// When the internal 8 MHz oscillator or an external source is chosen as the clock source,
-    /// the MPU-60X0 can operate in low power modes with the gyroscopes disabled. Upon power up,
-    /// the MPU-60X0clock source defaults to the internal oscillator. However, it is highly
-    /// recommended  that  the  device beconfigured  to  use  one  of  the  gyroscopes
-    /// (or  an  external  clocksource) as the clock reference for improved stability.
-    /// The clock source can be selected according to the following table...."
-    pub fn set_clock_source(&mut self, source: CLKSEL) -> Result<(), Mpu6050Error<E>> {
-        Ok(self.write_bits(
-            PWR_MGMT_1::ADDR,
-            PWR_MGMT_1::CLKSEL.bit,
-            PWR_MGMT_1::CLKSEL.length,
-            source as u8,
-        )?)
+    /// Gyro counts scaled to deg/s
+    pub(crate) fn gyro_deg_scale(&self, counts: Vector3d<i32>) -> Vector3d<f32> {
+        let mut gyro = Vector3d::<f32> {
+            x: counts.x as f32,
+            y: counts.y as f32,
+            z: counts.z as f32,
+        };
+        gyro *= 1.0 / self.gyro_sensitivity;
+        gyro
     }
 
-    /// get current clock source
-    pub fn get_clock_source(&mut self) -> Result<CLKSEL, Mpu6050Error<E>> {
-        let source = self.read_bits(
-            PWR_MGMT_1::ADDR,
-            PWR_MGMT_1::CLKSEL.bit,
-            PWR_MGMT_1::CLKSEL.length,
-        )?;
-        Ok(CLKSEL::from(source))
+    /// Same as `gyro_counts`, scaled to rad/s
+    pub(crate) fn gyro_from_raw(&self, raw: Vector3d<i16>, temp: Option<f32>) -> AngularVelocity {
+        self.gyro_scale(self.gyro_counts(raw, temp))
+    }
+
+    /// Converts a raw temperature reading to °C
+    pub(crate) fn temp_from_raw(&self, raw: i16) -> f32 {
+        (raw as f32 / self.temp_sensitivity) + self.temp_offset
+    }
+}
+
+/// Maximum number of gyro and accel calibration iterations
+pub(crate) const CALIBRATION_MAX_STEPS: usize = 20;
+
+/// Gyro calibration target. The measurement mean is in raw units (Count)/°/s. The target is to get it as
+/// close to 0 as possible, but it is not possible to get it to 0.
+/// we will aim for getting withing 1.5 counts/°/s to 0. For a 250°/s range, this is ~0.011 °/s error
+pub(crate) const GYRO_CALIBRATION_TARGET_MEAN: f32 = 1.5;
+
+/// Accel calibration target, in raw units (Count)/g at the 2g range. One step of the offset registers (2 LSB,
+/// as bit 0 is reserved) moves the reading by 16 counts at the 2g range, so we aim for getting within 8 counts
+/// of the target and leave the rest to the fine tune offsets
+pub(crate) const ACCEL_CALIBRATION_TARGET_MEAN: f32 = 8.0;
+
+/// Number of readings averaged per calibration step
+pub(crate) const CALIBRATION_MEASUREMENT_COUNT: i32 = 1000;
+
+/// Number of readings discarded before each calibration step
+pub(crate) const CALIBRATION_DISCARD_COUNT: i32 = 100;
+
+/// Delay between calibration readings
+pub(crate) const CALIBRATION_SAMPLE_DELAY_MS: u8 = 2;
+
+/// Tuning of the gyro calibration, see [`Mpu6050::calibrate_gyro_with_params`].
+/// More samples and a lower target mean give better offsets, but take longer
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GyroCalibrationParams {
+    /// Maximum number of calibration iterations
+    pub max_steps: usize,
+    /// Target mean per axis in raw units (counts), calibration is done when all axes are within it
+    pub target_mean: f32,
+    /// Number of readings averaged per iteration
+    pub sample_count: u16,
+    /// Number of readings discarded before each iteration
+    pub discard_count: u16,
+    /// Delay between readings in ms
+    pub settle_delay_ms: u8,
+}
+
+impl Default for GyroCalibrationParams {
+    fn default() -> Self {
+        GyroCalibrationParams {
+            max_steps: CALIBRATION_MAX_STEPS,
+            target_mean: GYRO_CALIBRATION_TARGET_MEAN,
+            sample_count: CALIBRATION_MEASUREMENT_COUNT as u16,
+            discard_count: CALIBRATION_DISCARD_COUNT as u16,
+            settle_delay_ms: CALIBRATION_SAMPLE_DELAY_MS,
+        }
+    }
+}
+
+/// Linear gyro bias over temperature, see [`Mpu6050::set_gyro_temp_compensation`].
+/// bias = intercept + slope * (temp - ref_temp), in raw gyro counts, from the user's own characterization
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GyroTempModel {
+    /// Bias change per axis in counts/°C
+    pub slope: Vector3d<f32>,
+    /// Bias per axis at ref_temp in counts
+    pub intercept: Vector3d<f32>,
+    /// Reference temperature in °C
+    pub ref_temp: f32,
+}
+
+impl GyroTempModel {
+    /// Modelled bias per axis at temp (°C), in raw gyro counts
+    pub fn bias(&self, temp: f32) -> Vector3d<f32> {
+        let mut bias = self.slope;
+        bias *= temp - self.ref_temp;
+        bias + self.intercept
+    }
+
+    /// Modelled bias, rounded to counts
+    fn bias_i32(&self, temp: f32) -> Vector3d<i32> {
+        let bias = self.bias(temp);
+        Vector3d::<i32> {
+            x: bias.x.round() as i32,
+            y: bias.y.round() as i32,
+            z: bias.z.round() as i32,
+        }
+    }
+}
+
+/// Progress reported by [`GyroCalibrator::step`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CalibrationProgress {
+    /// Calibration is running, fraction (0..1) of the maximum work (params.max_steps iterations) done
+    InProgress(f32),
+    /// Target mean reached, carries the gyro offsets written to the offset registers
+    Done(Vector3d<i32>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum CalibratorState {
+    Start,
+    Running,
+    Done(Vector3d<i32>),
+    Failed(Vector3d<f32>),
+}
+
+/// Non-blocking gyro calibration, the same algorithm as [`Mpu6050::calibrate_gyro_with_params`] split
+/// into short steps: each `step` takes at most `batch` readings and returns, so it can be pumped from a
+/// main loop or cooperative task. The sensor must be stationary until done.
+/// `params.settle_delay_ms` is not used, readings are spaced by the caller's loop period
+#[derive(Copy, Clone, Debug)]
+pub struct GyroCalibrator {
+    params: GyroCalibrationParams,
+    batch: u16,
+    state: CalibratorState,
+    iteration: usize,
+    readings: u32,
+    sum: Vector3d<i32>,
+}
+
+impl GyroCalibrator {
+    /// Creates a calibrator, taking at most batch readings (at least 1) per `step`.
+    /// Returns `InvalidCalibrationParams` for params.max_steps of 0
+    pub fn new<E>(params: GyroCalibrationParams, batch: u16) -> Result<Self, Mpu6050Error<E>> {
+        if params.max_steps == 0 {
+            return Err(Mpu6050Error::InvalidCalibrationParams);
+        }
+        Ok(GyroCalibrator {
+            params,
+            batch: batch.max(1),
+            state: CalibratorState::Start,
+            iteration: 0,
+            readings: 0,
+            sum: Vector3d::<i32>::default(),
+        })
+    }
+
+    /// Does a bounded amount of work. The first step resets the gyro offsets, the last one writes the found
+    /// offsets and fine tune offsets. Returns `CalibrationFailed` with the last mean, if the target isn't
+    /// reached within params.max_steps iterations, and keeps returning it on further steps
+    pub fn step<I, E>(
+        &mut self,
+        mpu: &mut Mpu6050<I>,
+    ) -> Result<CalibrationProgress, Mpu6050Error<E>>
+    where
+        I: Write<Error = E> + WriteRead<Error = E>,
+    {
+        match self.state {
+            CalibratorState::Done(offsets) => return Ok(CalibrationProgress::Done(offsets)),
+            CalibratorState::Failed(mean) => return Err(Mpu6050Error::CalibrationFailed(mean)),
+            CalibratorState::Start => {
+                mpu.set_gyro_offsets(0, 0, 0)?;
+                mpu.conv.gyro_fine_tune_offsets = Vector3d::<i32>::default();
+                self.state = CalibratorState::Running;
+            }
+            CalibratorState::Running => {}
+        }
+
+        let discard = self.params.discard_count as u32;
+        let count = self.params.sample_count.max(1) as u32;
+        for _ in 0..self.batch {
+            let rot = mpu.read_rot_i32(GYRO_REGX_H)?;
+            if self.readings >= discard {
+                self.sum += rot;
+            }
+            self.readings += 1;
+            if self.readings == discard + count {
+                break;
+            }
+        }
+
+        if self.readings == discard + count {
+            let mean = calibration_mean(self.sum, count as i32);
+            let (offsets, converged) =
+                mpu.calibrate_gyro_iteration(mean, self.params.target_mean)?;
+            self.iteration += 1;
+            self.readings = 0;
+            self.sum = Vector3d::<i32>::default();
+            if converged {
+                self.state = CalibratorState::Done(offsets);
+                return Ok(CalibrationProgress::Done(offsets));
+            }
+            if self.iteration >= self.params.max_steps {
+                self.state = CalibratorState::Failed(mean);
+                return Err(Mpu6050Error::CalibrationFailed(mean));
+            }
+        }
+
+        let per_iteration = (discard + count) as f32;
+        let done = self.iteration as f32 * per_iteration + self.readings as f32;
+        Ok(CalibrationProgress::InProgress(
+            done / (self.params.max_steps as f32 * per_iteration),
+        ))
+    }
+}
+
+/// Wrapper skipping reads in loops running faster than the sample rate: `read` only hits the bus once a
+/// sample period has passed since the last bus read, and returns the cached measurement otherwise.
+/// Time comes from a caller supplied tick in µs (e.g. a free running timer), wrap around is handled
+pub struct RateLimitedReader<I> {
+    mpu: Mpu6050<I>,
+    period_us: u32,
+    last_read_us: Option<u32>,
+    last: Measurement,
+}
+
+impl<I, E> RateLimitedReader<I>
+where
+    I: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Creates a reader for the sample rate (Hz) the device is configured for, see `set_sample_rate`.
+    /// The driver is expected to be initialized
+    pub fn new(mpu: Mpu6050<I>, sample_rate_hz: u16) -> Self {
+        RateLimitedReader {
+            mpu,
+            period_us: 1_000_000 / sample_rate_hz.max(1) as u32,
+            last_read_us: None,
+            last: Measurement::default(),
+        }
+    }
+
+    /// Reads a new measurement if at least one sample period passed since the last bus read
+    /// (or on the first call), returns the cached one otherwise. now_us is the current tick in µs
+    pub fn read(&mut self, now_us: u32) -> Result<Measurement, Mpu6050Error<E>> {
+        if let Some(last_read_us) = self.last_read_us {
+            if now_us.wrapping_sub(last_read_us) < self.period_us {
+                return Ok(self.last);
+            }
+        }
+        self.last = self.mpu.get_measurement()?;
+        self.last_read_us = Some(now_us);
+        Ok(self.last)
+    }
+
+    /// Last measurement read from the bus, default before the first read
+    pub fn last(&self) -> Measurement {
+        self.last
+    }
+
+    /// Mutable access to the driver
+    pub fn mpu_mut(&mut self) -> &mut Mpu6050<I> {
+        &mut self.mpu
+    }
+
+    /// Destroys the reader, returning the driver
+    pub fn release(self) -> Mpu6050<I> {
+        self.mpu
+    }
+}
+
+/// Time for the device to come up after waking it
+pub(crate) const WAKE_DELAY_MS: u16 = 100;
+
+/// Number of readings taken at the widest range by auto ranging
+pub(crate) const AUTO_RANGE_SAMPLES: u16 = 20;
+
+/// Interval between auto ranging readings
+pub(crate) const AUTO_RANGE_INTERVAL_MS: u8 = 5;
+
+/// Share of the full scale the peak may use after auto ranging, the rest is headroom
+pub(crate) const AUTO_RANGE_HEADROOM: f32 = 0.8;
+
+/// Settle time after switching the clock source
+pub(crate) const CLOCK_SETTLE_DELAY_MS: u8 = 50;
+
+/// Number of readings averaged per self test measurement
+pub(crate) const SELF_TEST_SAMPLE_COUNT: i32 = 50;
+
+/// Gyro factory trim (counts at 250°/s) for a 5 bit self test value, Register Map rev 4.2, chapter 4.1.
+/// The y axis trim is negative
+pub(crate) fn gyro_factory_trim(test: u8) -> f32 {
+    if test == 0 {
+        return 0.0;
+    }
+    25.0 * 131.0 * 1.046_f32.powf(test as f32 - 1.0)
+}
+
+/// Accel factory trim (counts at 8g) for a 5 bit self test value, Register Map rev 4.2, chapter 4.1
+pub(crate) fn accel_factory_trim(test: u8) -> f32 {
+    if test == 0 {
+        return 0.0;
+    }
+    4096.0 * 0.34 * (0.92_f32 / 0.34).powf((test as f32 - 1.0) / 30.0)
+}
+
+/// Change of a self test response from the factory trim in %
+pub(crate) fn self_test_deviation(response: f32, trim: f32) -> f32 {
+    (response - trim) / trim * 100.0
+}
+
+/// Mean of count summed up raw readings
+pub(crate) fn calibration_mean(sum: Vector3d<i32>, count: i32) -> Vector3d<f32> {
+    Vector3d::<f32> {
+        x: sum.x as f32 / count as f32,
+        y: sum.y as f32 / count as f32,
+        z: sum.z as f32 / count as f32,
+    }
+}
+
+/// Clamps an offset to the range of the 16 bit offset registers, instead of wrapping on the register write
+pub(crate) fn clamp_offset(offset: i32) -> i32 {
+    offset.clamp(i16::MIN as i32, i16::MAX as i32)
+}
+
+/// Calculates new gyro offsets during calibration. To converge on the right offsets, we take the current offset
+/// and substract the the mean/4, for every axis not yet within target_mean. Offsets are clamped to i16
+pub(crate) fn gyro_calibration_offsets(
+    offsets: Vector3d<i32>,
+    mean: Vector3d<f32>,
+    target_mean: f32,
+) -> Vector3d<i32> {
+    let mut updated_offsets = offsets;
+    if mean.x.abs() > target_mean {
+        updated_offsets.x =
+            clamp_offset(offsets.x - (mean.x.signum() * f32::max(mean.x.abs() / 4.0, 1.0)) as i32);
+    }
+    if mean.y.abs() > target_mean {
+        updated_offsets.y =
+            clamp_offset(offsets.y - (mean.y.signum() * f32::max(mean.y.abs() / 4.0, 1.0)) as i32);
+    }
+    if mean.z.abs() > target_mean {
+        updated_offsets.z =
+            clamp_offset(offsets.z - (mean.z.signum() * f32::max(mean.z.abs() / 4.0, 1.0)) as i32);
+    }
+    updated_offsets
+}
+
+/// Calculates new accel offsets during calibration, from the distance of the mean to the target (error).
+/// offset_scale converts measurement counts to offset register counts. The offset registers have a reserved
+/// bit 0, so every axis not yet within target_mean moves by at least 2. Offsets are clamped to i16
+pub(crate) fn accel_calibration_offsets(
+    offsets: Vector3d<i32>,
+    error: Vector3d<f32>,
+    target_mean: f32,
+    offset_scale: f32,
+) -> Vector3d<i32> {
+    let step = |offset: i32, error: f32| {
+        if error.abs() > target_mean {
+            clamp_offset(
+                offset - (error.signum() * f32::max(error.abs() * offset_scale, 2.0)) as i32,
+            )
+        } else {
+            offset
+        }
+    };
+    Vector3d::<i32> {
+        x: step(offsets.x, error.x),
+        y: step(offsets.y, error.y),
+        z: step(offsets.z, error.z),
+    }
+}
+
+/// Waits ms, in steps of at most 255 ms, as `DelayMs<u8>` can't wait longer in one call
+pub(crate) fn delay_ms_long<D: DelayMs<u8>>(delay: &mut D, ms: u32) {
+    let mut remaining = ms;
+    while remaining > 0 {
+        let step = remaining.min(u8::MAX as u32) as u8;
+        delay.delay_ms(step);
+        remaining -= step as u32;
+    }
+}
+
+/// Fine tune offsets for the mean left after calibration, below what the offset registers can resolve
+pub(crate) fn calibration_residual(mean: Vector3d<f32>) -> Vector3d<i32> {
+    Vector3d::<i32> {
+        x: -(mean.x.round() as i32),
+        y: -(mean.y.round() as i32),
+        z: -(mean.z.round() as i32),
+    }
+}
+
+/// Whether all axes of a calibration mean are within target_mean of 0
+pub(crate) fn calibration_converged(mean: Vector3d<f32>, target_mean: f32) -> bool {
+    mean.x.abs() < target_mean && mean.y.abs() < target_mean && mean.z.abs() < target_mean
+}
+
+impl<I, E> Mpu6050<I>
+where
+    I: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// Side effect free constructor with default sensitivies, no calibration
+    pub fn new(i2c: I) -> Self {
+        Mpu6050::new_with_addr(i2c, DEFAULT_SLAVE_ADDR)
+    }
+
+    /// Probes 0x68, then 0x69 (AD0 high) with a WHOAMI read and constructs with the address that
+    /// responds with a known chip ID, recording the model. Returns `DeviceNotFound` if neither does.
+    /// WHOAMI is readable while the device sleeps, `init` is still required
+    pub fn new_autodetect(i2c: I) -> Result<Self, Mpu6050Error<E>> {
+        let mut mpu = Mpu6050::new(i2c);
+        for addr in [DEFAULT_SLAVE_ADDR, ALT_SLAVE_ADDR] {
+            mpu.slave_addr = addr;
+            // bus errors are expected at the address without device
+            if mpu.verify().is_ok() {
+                return Ok(mpu);
+            }
+        }
+        Err(Mpu6050Error::DeviceNotFound)
+    }
+
+    /// custom sensitivity
+    pub fn new_with_sens(i2c: I, arange: AccelRange, grange: GyroRange) -> Self {
+        Mpu6050::new_with_addr_and_sens(i2c, DEFAULT_SLAVE_ADDR, arange, grange)
+    }
+
+    /// Same as `new`, but the chip address can be specified (e.g. 0x69, if the A0 pin is pulled up)
+    pub fn new_with_addr(i2c: I, slave_addr: u8) -> Self {
+        Mpu6050::new_with_addr_and_sens(i2c, slave_addr, AccelRange::G2, GyroRange::D250)
+    }
+
+    /// Combination of `new_with_sens` and `new_with_addr`. All other constructors end up here
+    pub fn new_with_addr_and_sens(
+        i2c: I,
+        slave_addr: u8,
+        arange: AccelRange,
+        grange: GyroRange,
+    ) -> Self {
+        Mpu6050 {
+            i2c,
+            slave_addr,
+            conv: Conversion::new(arange, grange),
+            model: None,
+            int_active_low: false,
+            mag_scale: None,
+            awake: false,
+            wake_check: false,
+            expected_chip_id: None,
+            pending_int: 0,
+        }
+    }
+
+    /// Changes the I2C address the driver talks to, e.g. to switch between 0x68 and 0x69 during bring-up
+    pub fn set_slave_addr(&mut self, addr: u8) -> Result<(), Mpu6050Error<E>> {
+        if !(0x08..=0x77).contains(&addr) {
+            return Err(Mpu6050Error::InvalidSlaveAddr(addr));
+        }
+        self.slave_addr = addr;
+        Ok(())
+    }
+
+    /// Current I2C address
+    pub fn slave_addr(&self) -> u8 {
+        self.slave_addr
+    }
+
+    /// Consumes the driver and returns the owned i2c bus, e.g. to hand it to another driver
+    pub fn release(self) -> I {
+        self.i2c
+    }
+
+    /// Mutable access to the i2c bus, e.g. for raw transactions on a shared bus
+    pub fn i2c_mut(&mut self) -> &mut I {
+        &mut self.i2c
+    }
+
+    /// Wakes MPU6050 with all sensors enabled (default)
+    fn wake<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
+        self.wake_with_delay(delay, WAKE_DELAY_MS)
+    }
+
+    /// Same as `wake`, waiting delay_ms for the device to come up
+    fn wake_with_delay<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        delay_ms: u16,
+    ) -> Result<(), Mpu6050Error<E>> {
+        self.wake_no_delay()?;
+        delay_ms_long(delay, delay_ms as u32);
+        Ok(())
+    }
+
+    /// Wakes MPU6050 with all sensors enabled, like `init` does, but returns right after the register write.
+    /// The device needs ~100ms to come up: other bus traffic is fine meanwhile, sensor readings are not
+    /// valid before. Does not verify the chip ID
+    pub fn wake_no_delay(&mut self) -> Result<(), Mpu6050Error<E>> {
+        // MPU6050 has sleep enabled by default -> set bit 0 to wake
+        // Set clock source to be PLL with x-axis gyroscope reference, bits 2:0 = 001 (See Register Map )
+        self.write_byte(PWR_MGMT_1::ADDR, 0x01)?;
+        self.conv.temp_enabled = true;
+        self.awake = true;
+        Ok(())
+    }
+
+    /// From Register map:
+    /// "An  internal  8MHz  oscillator,  gyroscope based  clock,or  external  sources  can  be
+    /// selected  as the MPU-60X0 clock source.
+    /// When the internal 8 MHz oscillator or an external source is chosen as the clock source,
+    /// the MPU-60X0 can operate in low power modes with the gyroscopes disabled. Upon power up,
+    /// the MPU-60X0clock source defaults to the internal oscillator. However, it is highly
+    /// recommended  that  the  device beconfigured  to  use  one  of  the  gyroscopes
+    /// (or  an  external  clocksource) as the clock reference for improved stability.
+    /// The clock source can be selected according to the following table...."
+    pub fn set_clock_source(&mut self, source: CLKSEL) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            PWR_MGMT_1::ADDR,
+            PWR_MGMT_1::CLKSEL.bit,
+            PWR_MGMT_1::CLKSEL.length,
+            source as u8,
+        )
+    }
+
+    /// Same as `set_clock_source`, but waits for the new clock to settle (gyro start-up time is 30ms typ.),
+    /// so the following readings are not taken while the PLL locks
+    pub fn set_clock_source_blocking<D: DelayMs<u8>>(
+        &mut self,
+        source: CLKSEL,
+        delay: &mut D,
+    ) -> Result<(), Mpu6050Error<E>> {
+        self.set_clock_source(source)?;
+        delay.delay_ms(CLOCK_SETTLE_DELAY_MS);
+        Ok(())
+    }
+
+    /// Selects the internal 8MHz oscillator (CLKSEL 0), the default after power on. It drifts more than the
+    /// gyro PLL, but keeps running with all gyros in standby, e.g. for accel only operation
+    pub fn use_internal_clock(&mut self) -> Result<(), Mpu6050Error<E>> {
+        self.set_clock_source(CLKSEL::OSCILL)
+    }
+
+    /// Selects the PLL with X axis gyro reference (CLKSEL 1). From the register map: "it is highly
+    /// recommended that the device be configured to use one of the gyroscopes (or an external clock
+    /// source) as the clock reference for improved stability". Needs the X gyro running
+    pub fn use_gyro_clock(&mut self) -> Result<(), Mpu6050Error<E>> {
+        self.set_clock_source(CLKSEL::GXAXIS)
+    }
+
+    /// Stops the clock and keeps the timing generator in reset (CLKSEL 7), no new samples are taken
+    /// until another clock source is selected
+    pub fn stop_clock(&mut self) -> Result<(), Mpu6050Error<E>> {
+        self.set_clock_source(CLKSEL::STOP)
+    }
+
+    /// get current clock source
+    pub fn get_clock_source(&mut self) -> Result<CLKSEL, Mpu6050Error<E>> {
+        let source = self.read_bits(
+            PWR_MGMT_1::ADDR,
+            PWR_MGMT_1::CLKSEL.bit,
+            PWR_MGMT_1::CLKSEL.length,
+        )?;
+        Ok(CLKSEL::from(source))
+    }
+
+    /// Init wakes MPU6050 and verifies register addr, e.g. in i2c.
+    /// Applies the ranges given at construction (default: 2g, 250°/s), the accel HPF is left untouched,
+    /// so `init` may be called before or after `setup_motion_detection`
+    pub fn init<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
+        self.init_with_config(delay, Mpu6050Config::default())
+    }
+
+    /// Same as `init`, additionally applying all settings given in config.
+    /// A bus error on the first access (wake, or WHOAMI with `skip_wake`) returns `DeviceNotResponding`,
+    /// as a missing device doesn't acknowledge its address. Later bus errors are returned as they are
+    pub fn init_with_config<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        config: Mpu6050Config,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let addr = self.slave_addr;
+        let not_responding = |e| match e {
+            Mpu6050Error::I2c(_) => Mpu6050Error::DeviceNotResponding(addr),
+            e => e,
+        };
+        if config.skip_wake {
+            self.verify().map_err(not_responding)?;
+            self.awake = true;
+        } else {
+            self.wake_with_delay(delay, config.wake_delay_ms.unwrap_or(WAKE_DELAY_MS))
+                .map_err(not_responding)?;
+            self.verify()?;
+        }
+        if let Some(source) = config.clock_source {
+            self.set_clock_source(source)?;
+        }
+        self.set_accel_range(config.accel_range.unwrap_or(self.conv.acc_range))?;
+        self.set_gyro_range(config.gyro_range.unwrap_or(self.conv.gyro_range))?;
+        if let Some(hpf) = config.accel_hpf {
+            self.set_accel_hpf(hpf)?;
+        }
+        // DLPF first, it determines the base rate of the sample rate divider
+        if let Some(dlpf) = config.dlpf {
+            self.set_dlpf(dlpf)?;
+        }
+        if let Some(hz) = config.sample_rate {
+            self.set_sample_rate(hz)?;
+        }
+        Ok(())
+    }
+
+    /// Same as `init`, but puts the accelerometer axes in standby (PWR_MGMT_2) to save power.
+    /// Accel readings stay at their last value
+    pub fn init_gyro_only<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
+        self.init(delay)?;
+        self.set_standby([true; 3], [false; 3])
+    }
+
+    /// Same as `init`, but puts the gyro axes in standby (PWR_MGMT_2) to save power. Selects the internal
+    /// 8MHz oscillator, the gyro PLL can't be used without the gyros. Gyro readings stay at their last value
+    pub fn init_accel_only<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let config = Mpu6050Config {
+            clock_source: Some(CLKSEL::OSCILL),
+            ..Default::default()
+        };
+        self.init_with_config(delay, config)?;
+        self.set_standby([false; 3], [true; 3])
+    }
+
+    /// Verifies device identity with the WHOAMI Register, independent of the slave address.
+    /// Accepts all models in [`DeviceModel`] and records the detected one
+    fn verify(&mut self) -> Result<(), Mpu6050Error<E>> {
+        let chip_id = self.who_am_i()?;
+        match self.model_for_chip_id(chip_id) {
+            Some(model) => {
+                self.model = Some(model);
+                Ok(())
+            }
+            None => Err(Mpu6050Error::InvalidChipId(chip_id)),
+        }
+    }
+
+    /// get raw content of the WHOAMI register
+    pub fn who_am_i(&mut self) -> Result<u8, Mpu6050Error<E>> {
+        self.read_byte(WHOAMI)
+    }
+
+    /// get raw content of the (undocumented) product ID register, see [`PRODUCT_ID`]
+    pub fn product_id(&mut self) -> Result<u8, Mpu6050Error<E>> {
+        self.read_byte(PRODUCT_ID)
+    }
+
+    /// Cheap health check: reads WHOAMI and returns whether a known chip ID came back (the one detected
+    /// by `init`, if any). Bus errors, e.g. from a disconnected sensor, return false instead of an error
+    pub fn ping(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        let chip_id = match self.who_am_i() {
+            Ok(id) => id,
+            Err(_) => return Ok(false),
+        };
+        Ok(match self.model {
+            Some(model) => model.chip_id() == chip_id || self.expected_chip_id == Some(chip_id),
+            None => self.model_for_chip_id(chip_id).is_some(),
+        })
+    }
+
+    /// Health check of the measurement itself, unlike `ping`: reads accel, temp and gyro samples times, one
+    /// sample period apart, and returns true if the raw bytes never change. Sensor noise changes a live
+    /// reading every sample, frozen data means a sleeping device or a failed clock. The period follows
+    /// `output_data_rate`, or the wake frequency in the accelerometer only low power mode (up to 800 ms).
+    /// Returns `InvalidSampleCount` for less than 2 samples
+    pub fn detect_stall<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        samples: u8,
+    ) -> Result<bool, Mpu6050Error<E>> {
+        if samples < 2 {
+            return Err(Mpu6050Error::InvalidSampleCount);
+        }
+        // in cycle mode, new data only arrives at the wake frequency
+        let rate = if self.read_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::CYCLE)? != 0 {
+            self.get_low_power_wake_frequency()?.frequency()
+        } else {
+            self.output_data_rate()?
+        };
+        let period_ms = (1000.0 / rate).ceil() as u32 + 1;
+
+        let mut first: [u8; 14] = [0; 14];
+        self.read_bytes(ACC_REGX_H, &mut first)?;
+        for _ in 1..samples {
+            delay_ms_long(delay, period_ms);
+            let mut buf: [u8; 14] = [0; 14];
+            self.read_bytes(ACC_REGX_H, &mut buf)?;
+            if buf != first {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Accepts chip_id in `init` (and `ping`) in addition to the known models, for register compatible clones
+    /// reporting a different WHOAMI. Such a clone is recorded as [`DeviceModel::Mpu6050`]. None (the default)
+    /// only accepts the known models
+    pub fn set_expected_chip_id(&mut self, chip_id: Option<u8>) {
+        self.expected_chip_id = chip_id;
+    }
+
+    /// Model accepted for chip_id, see `set_expected_chip_id`
+    fn model_for_chip_id(&self, chip_id: u8) -> Option<DeviceModel> {
+        if self.expected_chip_id == Some(chip_id) {
+            return Some(DeviceModel::Mpu6050);
+        }
+        DeviceModel::from_chip_id(chip_id)
+    }
+
+    /// Device model detected by `init`, None before a successful init
+    pub fn get_device_model(&self) -> Option<DeviceModel> {
+        self.model
+    }
+
+    /// setup motion detection
+    /// sources:
+    /// * https://github.com/kriswiner/MPU6050/blob/a7e0c8ba61a56c5326b2bcd64bc81ab72ee4616b/MPU6050IMU.ino#L486
+    /// * https://arduino.stackexchange.com/a/48430
+    ///
+    /// Call after `init` (or re-init) if in doubt: `init` keeps the HPF, but `init_with_config` with
+    /// `accel_hpf` set, `set_accel_hpf` and `reset` override the HPF motion detection depends on
+    pub fn setup_motion_detection(&mut self, config: MotionConfig) -> Result<(), Mpu6050Error<E>> {
+        self.write_byte(PWR_MGMT_1::ADDR, 0x00)?;
+        self.conv.temp_enabled = true;
+        self.awake = true;
+        // optional? self.write_byte(0x68, 0x07)?; // Reset all internal signal paths in the MPU-6050 by writing 0x07 to register 0x68;
+        // select how to use the interrupt pin: an active high, push-pull signal that stays until register (decimal) 58 is read
+        self.configure_interrupt_pin(IntPinConfig {
+            latch_until_cleared: true,
+            ..Default::default()
+        })?;
+        // Set the Digital High Pass Filter, bits 2:0 of register 28 (==0x1C). For example _5 for 5Hz. (These 3 bits are grey in the data sheet, but they are used! Leaving them 0 means the filter always outputs 0.)
+        self.set_accel_hpf(config.accel_hpf)?;
+        self.write_bytes(MOT_THR, &[config.threshold, config.duration])?;
+        // to register 0x69, write the motion detection decrement and a few other settings (for example write 0x15 to set both free-fall and motion decrements to 1 and accelerometer start-up delay to 5ms total by adding 1ms. )
+        self.write_byte(MOT_DETECT_CONTROL::ADDR, 0x15)?;
+        // enable motion detection interrupt only
+        self.set_interrupt_enable(InterruptEnable {
+            motion: true,
+            ..Default::default()
+        })?;
+        Ok(())
+    }
+
+    /// setup motion detection with `MotionConfig::default()`: threshold 10 (20mg), duration 40ms, accel HPF 5Hz
+    pub fn setup_motion_detection_default(&mut self) -> Result<(), Mpu6050Error<E>> {
+        self.setup_motion_detection(MotionConfig::default())
+    }
+
+    /// set motion detection threshold, LSB = 2mg
+    pub fn set_motion_threshold(&mut self, thr: u8) -> Result<(), Mpu6050Error<E>> {
+        self.write_byte(MOT_THR, thr)
+    }
+
+    /// get motion detection threshold, LSB = 2mg
+    pub fn get_motion_threshold(&mut self) -> Result<u8, Mpu6050Error<E>> {
+        self.read_byte(MOT_THR)
+    }
+
+    /// set motion detection duration, LSB = 1ms
+    pub fn set_motion_duration(&mut self, dur: u8) -> Result<(), Mpu6050Error<E>> {
+        self.write_byte(MOT_DUR, dur)
+    }
+
+    /// get motion detection duration, LSB = 1ms
+    pub fn get_motion_duration(&mut self) -> Result<u8, Mpu6050Error<E>> {
+        self.read_byte(MOT_DUR)
+    }
+
+    /// configure the electrical behaviour of the INT pin, see `IntPinConfig`. Other bits of INT_PIN_CFG are kept
+    pub fn configure_interrupt_pin(&mut self, cfg: IntPinConfig) -> Result<(), Mpu6050Error<E>> {
+        let mut byte = self.read_byte(INT_PIN_CFG::ADDR)?;
+        bits::set_bit(&mut byte, INT_PIN_CFG::INT_LEVEL, cfg.active_low);
+        bits::set_bit(&mut byte, INT_PIN_CFG::INT_OPEN, cfg.open_drain);
+        bits::set_bit(
+            &mut byte,
+            INT_PIN_CFG::LATCH_INT_EN,
+            cfg.latch_until_cleared,
+        );
+        bits::set_bit(&mut byte, INT_PIN_CFG::INT_RD_CLEAR, cfg.clear_on_any_read);
+        self.write_byte(INT_PIN_CFG::ADDR, byte)?;
+        self.int_active_low = cfg.active_low;
+        Ok(())
+    }
+
+    /// get INT pin configuration
+    pub fn get_interrupt_pin_config(&mut self) -> Result<IntPinConfig, Mpu6050Error<E>> {
+        let byte = self.read_byte(INT_PIN_CFG::ADDR)?;
+        let cfg = IntPinConfig {
+            active_low: bits::get_bit(byte, INT_PIN_CFG::INT_LEVEL) != 0,
+            open_drain: bits::get_bit(byte, INT_PIN_CFG::INT_OPEN) != 0,
+            latch_until_cleared: bits::get_bit(byte, INT_PIN_CFG::LATCH_INT_EN) != 0,
+            clear_on_any_read: bits::get_bit(byte, INT_PIN_CFG::INT_RD_CLEAR) != 0,
+        };
+        self.int_active_low = cfg.active_low;
+        Ok(cfg)
+    }
+
+    /// Reads a measurement if the INT pin (wired to int_pin) signals new data, None otherwise. The pin level is
+    /// checked first, respecting the polarity set with `configure_interrupt_pin`, so no bus traffic happens
+    /// while it's inactive. On an active pin INT_STATUS is read, which clears a latched interrupt, and the
+    /// measurement is only read for a data ready interrupt. Requires the data ready interrupt to be enabled,
+    /// see `set_interrupt_enable`
+    pub fn read_when_ready<P: InputPin>(
+        &mut self,
+        int_pin: &P,
+    ) -> Result<Option<Measurement>, Mpu6050Error<E>> {
+        let high = int_pin.is_high().map_err(|_| Mpu6050Error::Pin)?;
+        if high == self.int_active_low {
+            return Ok(None);
+        }
+        if !self.get_interrupt_status()?.data_ready {
+            return Ok(None);
+        }
+        Ok(Some(self.get_measurement()?))
+    }
+
+    /// get whether or not motion has been detected (INT_STATUS, MOT_INT).
+    /// NOTE: consumes all interrupt status bits, see `get_interrupt_status`
+    pub fn get_motion_detected(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.get_interrupt_status()?.motion)
+    }
+
+    /// get axis and direction of the last detected motion (MOT_DETECT_STATUS).
+    /// NOTE: reading MOT_DETECT_STATUS clears it
+    pub fn get_motion_detect_status(&mut self) -> Result<MotionDetectStatus, Mpu6050Error<E>> {
+        Ok(MotionDetectStatus::from(
+            self.read_byte(MOT_DETECT_STATUS::ADDR)?,
+        ))
+    }
+
+    /// enable, disable interrupt sources. DMP and PLL ready bits of INT_ENABLE are kept
+    pub fn set_interrupt_enable(&mut self, en: InterruptEnable) -> Result<(), Mpu6050Error<E>> {
+        let mut byte = self.read_byte(INT_ENABLE::ADDR)?;
+        bits::set_bit(&mut byte, INT_ENABLE::DATA_RDY_EN, en.data_ready);
+        bits::set_bit(&mut byte, INT_ENABLE::FIFO_OFLOW_EN, en.fifo_overflow);
+        bits::set_bit(&mut byte, INT_ENABLE::I2C_MST_INT_EN, en.i2c_mst);
+        bits::set_bit(&mut byte, INT_ENABLE::MOT_EN, en.motion);
+        bits::set_bit(&mut byte, INT_ENABLE::ZMOT_EN, en.zero_motion);
+        bits::set_bit(&mut byte, INT_ENABLE::FF_EN, en.free_fall);
+        self.write_byte(INT_ENABLE::ADDR, byte)
+    }
+
+    /// get enabled interrupt sources
+    pub fn get_interrupt_enable(&mut self) -> Result<InterruptEnable, Mpu6050Error<E>> {
+        Ok(InterruptEnable::from(self.read_byte(INT_ENABLE::ADDR)?))
+    }
+
+    /// Get all interrupt status bits with a single read of INT_STATUS, including those seen by earlier
+    /// `peek_interrupt_status` calls.
+    /// NOTE: the device clears INT_STATUS on every read, so this consumes the status: a second call only
+    /// reports interrupts fired in between. The same goes for `get_motion_detected`, `get_free_fall_detected`,
+    /// `get_zero_motion_detected` and `fifo_overflow`, which each consume all bits, not only their own.
+    /// Use `peek_interrupt_status` to check several bits without losing the others
+    pub fn get_interrupt_status(&mut self) -> Result<InterruptStatus, Mpu6050Error<E>> {
+        let byte = self.read_byte(INT_STATUS::ADDR)? | self.pending_int;
+        self.pending_int = 0;
+        Ok(InterruptStatus::from(byte))
+    }
+
+    /// Reads INT_STATUS without consuming it: the device clears the register on the read anyway, so the
+    /// driver keeps the bits seen until a consuming read (`get_interrupt_status`, `clear_interrupts`, ...)
+    /// returns them. Bits already cleared by other reads, with [`IntPinConfig::clear_on_any_read`] set,
+    /// can't be seen
+    pub fn peek_interrupt_status(&mut self) -> Result<InterruptStatus, Mpu6050Error<E>> {
+        self.pending_int |= self.read_byte(INT_STATUS::ADDR)?;
+        Ok(InterruptStatus::from(self.pending_int))
+    }
+
+    /// Clears latched interrupts (see [`IntPinConfig::latch_until_cleared`]) by reading INT_STATUS,
+    /// returns which interrupts fired. Call it after handling an interrupt to release the INT pin.
+    /// With [`IntPinConfig::clear_on_any_read`] set, any register read clears the interrupt as well,
+    /// so the returned status may miss interrupts already cleared by other reads
+    pub fn clear_interrupts(&mut self) -> Result<InterruptStatus, Mpu6050Error<E>> {
+        self.get_interrupt_status()
+    }
+
+    /// setup free fall detection and enable its interrupt. Free fall is detected when all accel axes are
+    /// below threshold (FF_THR, LSB = 2mg) for duration (FF_DUR, LSB = 1ms)
+    pub fn setup_free_fall_detection(
+        &mut self,
+        threshold: u8,
+        duration: u8,
+    ) -> Result<(), Mpu6050Error<E>> {
+        self.write_bytes(FF_THR, &[threshold, duration])?;
+        self.write_bit(INT_ENABLE::ADDR, INT_ENABLE::FF_EN, true)
+    }
+
+    /// get whether or not free fall has been detected (INT_STATUS, FF_INT).
+    /// NOTE: consumes all interrupt status bits, see `get_interrupt_status`
+    pub fn get_free_fall_detected(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.get_interrupt_status()?.free_fall)
+    }
+
+    /// setup zero motion detection and enable its interrupt.
+    /// From the register map: the zero motion duration counter increments while the absolute (high pass
+    /// filtered) accel measurements of all 3 axes are below threshold (ZRMOT_THR, LSB = 2mg), and the
+    /// interrupt fires once it reaches duration (ZRMOT_DUR, LSB = 64ms). The counter resets as soon as
+    /// motion is detected again, which fires the interrupt a second time.
+    pub fn setup_zero_motion_detection(
+        &mut self,
+        threshold: u8,
+        duration: u8,
+    ) -> Result<(), Mpu6050Error<E>> {
+        self.write_bytes(ZRMOT_THR, &[threshold, duration])?;
+        self.write_bit(INT_ENABLE::ADDR, INT_ENABLE::ZMOT_EN, true)
+    }
+
+    /// get whether or not a zero motion interrupt has been generated (INT_STATUS, ZMOT_INT).
+    /// Set on both transitions (motion -> zero motion, zero motion -> motion), MOT_DETECT_STATUS
+    /// MOT_ZRMOT tells which one.
+    /// NOTE: consumes all interrupt status bits, see `get_interrupt_status`
+    pub fn get_zero_motion_detected(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.get_interrupt_status()?.zero_motion)
+    }
+
+    /// set accel high pass filter mode. Motion and zero motion detection work on the filtered accel data,
+    /// `_RESET` keeps the filter output at 0 so they never fire: it's rejected with `InvalidConfig` while
+    /// either interrupt is enabled, disable them with `set_interrupt_enable` first
+    pub fn set_accel_hpf(&mut self, mode: ACCEL_HPF) -> Result<(), Mpu6050Error<E>> {
+        if mode == ACCEL_HPF::_RESET {
+            let en = self.get_interrupt_enable()?;
+            if en.motion || en.zero_motion {
+                return Err(Mpu6050Error::InvalidConfig);
+            }
+        }
+        self.write_bits(
+            ACCEL_CONFIG::ADDR,
+            ACCEL_CONFIG::ACCEL_HPF.bit,
+            ACCEL_CONFIG::ACCEL_HPF.length,
+            mode as u8,
+        )
+    }
+
+    /// get accel high pass filter mode
+    pub fn get_accel_hpf(&mut self) -> Result<ACCEL_HPF, Mpu6050Error<E>> {
+        let mode: u8 = self.read_bits(
+            ACCEL_CONFIG::ADDR,
+            ACCEL_CONFIG::ACCEL_HPF.bit,
+            ACCEL_CONFIG::ACCEL_HPF.length,
+        )?;
+
+        Ok(ACCEL_HPF::from(mode))
+    }
+
+    /// set external frame synchronization (CONFIG, EXT_SYNC_SET). Read-modify-write, DLPF_CFG is kept
+    pub fn set_fsync(&mut self, src: ExtSync) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            CONFIG::ADDR,
+            CONFIG::EXT_SYNC_SET.bit,
+            CONFIG::EXT_SYNC_SET.length,
+            src as u8,
+        )
+    }
+
+    /// get external frame synchronization
+    pub fn get_fsync(&mut self) -> Result<ExtSync, Mpu6050Error<E>> {
+        let set = self.read_bits(
+            CONFIG::ADDR,
+            CONFIG::EXT_SYNC_SET.bit,
+            CONFIG::EXT_SYNC_SET.length,
+        )?;
+
+        Ok(ExtSync::from(set))
+    }
+
+    /// set digital low pass filter, for both accel and gyro.
+    /// Note: the DLPF also determines the gyro output rate, which is the base of the sample rate:
+    /// 8 kHz with the filter disabled, 1 kHz otherwise. The sample rate is
+    /// gyro output rate / (1 + SMPLRT_DIV), so changing the DLPF changes the sample rate as well.
+    pub fn set_dlpf(&mut self, cfg: DLPF) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            CONFIG::ADDR,
+            CONFIG::DLPF_CFG.bit,
+            CONFIG::DLPF_CFG.length,
+            cfg as u8,
+        )
+    }
+
+    /// get digital low pass filter config
+    pub fn get_dlpf(&mut self) -> Result<DLPF, Mpu6050Error<E>> {
+        let cfg = self.read_bits(CONFIG::ADDR, CONFIG::DLPF_CFG.bit, CONFIG::DLPF_CFG.length)?;
+
+        Ok(DLPF::from(cfg))
+    }
+
+    /// Accelerometer bandwidth in Hz for the current DLPF config, see [`DLPF::accel_bandwidth`]
+    /// (and [`DLPF::accel_delay_ms`] for the delay)
+    pub fn effective_accel_bandwidth(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        Ok(self.get_dlpf()?.accel_bandwidth())
+    }
+
+    /// Gyro bandwidth in Hz for the current DLPF config, see [`DLPF::gyro_bandwidth`]
+    /// (and [`DLPF::gyro_delay_ms`] for the delay)
+    pub fn effective_gyro_bandwidth(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        Ok(self.get_dlpf()?.gyro_bandwidth())
+    }
+
+    /// set sample rate divider. The sample rate is gyro output rate / (1 + div),
+    /// where the gyro output rate is 8 kHz with the DLPF disabled, 1 kHz otherwise
+    pub fn set_sample_rate_divider(&mut self, div: u8) -> Result<(), Mpu6050Error<E>> {
+        self.write_byte(SMPLRT_DIV, div)
+    }
+
+    /// get sample rate divider
+    pub fn get_sample_rate_divider(&mut self) -> Result<u8, Mpu6050Error<E>> {
+        self.read_byte(SMPLRT_DIV)
+    }
+
+    /// Set sample rate in Hz, computing the divider from the current DLPF config
+    /// (see `set_sample_rate_divider`). Rates that aren't an integer fraction of the gyro output
+    /// rate are rounded to the closest achievable one. Returns `InvalidSampleRate` if hz is 0, above the
+    /// gyro output rate or below gyro output rate / 256.
+    /// Note: the accel output rate is 1 kHz, so rates above that repeat accel samples.
+    pub fn set_sample_rate(&mut self, hz: u16) -> Result<(), Mpu6050Error<E>> {
+        let base = self.get_dlpf()?.gyro_output_rate();
+        if hz == 0 || hz > base {
+            return Err(Mpu6050Error::InvalidSampleRate(hz));
+        }
+        let div = (base as f32 / hz as f32).round() as u16 - 1;
+        if div > u8::MAX as u16 {
+            return Err(Mpu6050Error::InvalidSampleRate(hz));
+        }
+        self.set_sample_rate_divider(div as u8)
+    }
+
+    /// Effective sample rate in Hz, gyro output rate / (1 + SMPLRT_DIV) for the current DLPF config and
+    /// divider, e.g. as dt = 1 / rate for the fusion filters. The accel output rate is 1 kHz regardless,
+    /// above it (DLPF disabled) the same accel sample is output more than once
+    pub fn output_data_rate(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        let base = self.get_dlpf()?.gyro_output_rate();
+        let div = self.get_sample_rate_divider()?;
+        Ok(base as f32 / (1.0 + div as f32))
+    }
+
+    /// Set gyro range, and update sensitivity accordingly
+    pub fn set_gyro_range(&mut self, range: GyroRange) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            GYRO_CONFIG::ADDR,
+            GYRO_CONFIG::FS_SEL.bit,
+            GYRO_CONFIG::FS_SEL.length,
+            range as u8,
+        )?;
+
+        self.conv.gyro_sensitivity = range.sensitivity();
+        self.conv.gyro_range = range;
+        Ok(())
+    }
+
+    /// get current gyro range
+    pub fn get_gyro_range(&mut self) -> Result<GyroRange, Mpu6050Error<E>> {
+        let byte = self.read_bits(
+            GYRO_CONFIG::ADDR,
+            GYRO_CONFIG::FS_SEL.bit,
+            GYRO_CONFIG::FS_SEL.length,
+        )?;
+
+        Ok(GyroRange::from(byte))
+    }
+
+    /// set accel range, and update sensitivy accordingly
+    pub fn set_accel_range(&mut self, range: AccelRange) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            ACCEL_CONFIG::ADDR,
+            ACCEL_CONFIG::FS_SEL.bit,
+            ACCEL_CONFIG::FS_SEL.length,
+            range as u8,
+        )?;
+
+        self.conv.acc_sensitivity = range.sensitivity();
+        self.conv.acc_range = range;
+        Ok(())
+    }
+
+    /// Picks the smallest accel range fitting the current motion, for the best resolution: samples for 100ms
+    /// at 16g, then sets the smallest range keeping the peak below 80% of its full scale. The range is
+    /// changed while sampling and left at the chosen one, which is returned. Only motion during sampling
+    /// counts, later stronger motion may saturate, see `is_saturated`
+    pub fn auto_range_accel<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<AccelRange, Mpu6050Error<E>> {
+        self.set_accel_range(AccelRange::G16)?;
+        let peak = self.peak_abs_raw(ACC_REGX_H, delay)?;
+        let range = [AccelRange::G2, AccelRange::G4, AccelRange::G8]
+            .iter()
+            .copied()
+            .find(|r| fits_range(peak, r.sensitivity() / AccelRange::G16.sensitivity()))
+            .unwrap_or(AccelRange::G16);
+        self.set_accel_range(range)?;
+        Ok(range)
+    }
+
+    /// Same as `auto_range_accel` for the gyro, sampling at 2000°/s
+    pub fn auto_range_gyro<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<GyroRange, Mpu6050Error<E>> {
+        self.set_gyro_range(GyroRange::D2000)?;
+        let peak = self.peak_abs_raw(GYRO_REGX_H, delay)?;
+        let range = [GyroRange::D250, GyroRange::D500, GyroRange::D1000]
+            .iter()
+            .copied()
+            .find(|r| fits_range(peak, r.sensitivity() / GyroRange::D2000.sensitivity()))
+            .unwrap_or(GyroRange::D2000);
+        self.set_gyro_range(range)?;
+        Ok(range)
+    }
+
+    /// Largest absolute raw value of any axis over the auto ranging readings
+    fn peak_abs_raw<D: DelayMs<u8>>(
+        &mut self,
+        reg: u8,
+        delay: &mut D,
+    ) -> Result<i32, Mpu6050Error<E>> {
+        let mut peak: i32 = 0;
+        for _ in 0..AUTO_RANGE_SAMPLES {
+            // the first wait also lets the new range take effect
+            delay.delay_ms(AUTO_RANGE_INTERVAL_MS);
+            let raw = self.read_rot_raw(reg)?;
+            for v in [raw.x, raw.y, raw.z] {
+                peak = peak.max((v as i32).abs());
+            }
+        }
+        Ok(peak)
+    }
+
+    /// Accel sensitivity (LSB/g) used for scaling, set with the accel range
+    pub fn acc_sensitivity(&self) -> f32 {
+        self.conv.acc_sensitivity
+    }
+
+    /// Gyro sensitivity (LSB/(°/s)) used for scaling, set with the gyro range
+    pub fn gyro_sensitivity(&self) -> f32 {
+        self.conv.gyro_sensitivity
+    }
+
+    /// get current accel_range
+    pub fn get_accel_range(&mut self) -> Result<AccelRange, Mpu6050Error<E>> {
+        let byte = self.read_bits(
+            ACCEL_CONFIG::ADDR,
+            ACCEL_CONFIG::FS_SEL.bit,
+            ACCEL_CONFIG::FS_SEL.length,
+        )?;
+
+        Ok(AccelRange::from(byte))
+    }
+
+    /// reset device
+    pub fn reset_device<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::DEVICE_RESET, true)?;
+        delay.delay_ms(100u8);
+        // Note: Reset sets sleep to true! Section register map: resets PWR_MGMT to 0x40
+        self.conv.temp_enabled = true;
+        self.int_active_low = false;
+        self.awake = false;
+        self.pending_int = 0;
+        Ok(())
+    }
+
+    /// Recovers a hung sensor in one call: `reset_device` (which leaves the device asleep), then `init`, which
+    /// wakes it and verifies the chip ID. All registers are back at their defaults, the ranges set at
+    /// construction are dropped for 2g and 250°/s. Offsets from calibration and a magnetometer setup are lost,
+    /// repeat them if needed
+    pub fn reboot<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
+        self.reset_device(delay)?;
+        self.conv.acc_range = AccelRange::G2;
+        self.conv.gyro_range = GyroRange::D250;
+        self.conv.acc_fine_tune_offsets = Vector3d::<i32>::default();
+        self.conv.gyro_fine_tune_offsets = Vector3d::<i32>::default();
+        self.mag_scale = None;
+        self.init(delay)
+    }
+
+    /// enable, disable sleep of sensor
+    pub fn set_sleep_enabled(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::SLEEP, enable)?;
+        self.awake = !enable;
+        Ok(())
+    }
+
+    /// get sleep status
+    pub fn get_sleep_enabled(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        let sleep = self.read_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::SLEEP)? != 0;
+        self.awake = !sleep;
+        Ok(sleep)
+    }
+
+    /// Enables, disables the wake check: sensor reads return `Asleep` instead of stale data while the device
+    /// sleeps, e.g. when reading before `init`. Off by default. The check tracks the sleep state as set through
+    /// this driver (`init`, `set_sleep_enabled`, `reset_device`, ...) and costs no bus traffic, refresh it
+    /// with `get_sleep_enabled` after changing PWR_MGMT_1 otherwise
+    pub fn set_wake_check(&mut self, enable: bool) {
+        self.wake_check = enable;
+    }
+
+    /// Returns `Asleep` if the wake check is enabled and the device sleeps
+    fn check_awake(&self) -> Result<(), Mpu6050Error<E>> {
+        if self.wake_check && !self.awake {
+            return Err(Mpu6050Error::Asleep);
+        }
+        Ok(())
+    }
+
+    /// Accelerometer only low power mode: the device sleeps and wakes up at wake_freq to take a single
+    /// accel sample. Sets CYCLE=1, SLEEP=0, TEMP_DIS=1 and puts all gyro axes in standby. As the gyros are
+    /// off, the clock source is switched to the internal oscillator.
+    /// Combined with `setup_motion_detection` this gives wake on motion.
+    pub fn set_low_power_accel_mode(
+        &mut self,
+        wake_freq: LpWakeCtrl,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let mut pwr_mgmt_2 = self.read_byte(PWR_MGMT_2::ADDR)?;
+        bits::set_bits(
+            &mut pwr_mgmt_2,
+            PWR_MGMT_2::LP_WAKE_CTRL.bit,
+            PWR_MGMT_2::LP_WAKE_CTRL.length,
+            wake_freq as u8,
+        );
+        bits::set_bit(&mut pwr_mgmt_2, PWR_MGMT_2::STBY_XA, false);
+        bits::set_bit(&mut pwr_mgmt_2, PWR_MGMT_2::STBY_YA, false);
+        bits::set_bit(&mut pwr_mgmt_2, PWR_MGMT_2::STBY_ZA, false);
+        bits::set_bit(&mut pwr_mgmt_2, PWR_MGMT_2::STBY_XG, true);
+        bits::set_bit(&mut pwr_mgmt_2, PWR_MGMT_2::STBY_YG, true);
+        bits::set_bit(&mut pwr_mgmt_2, PWR_MGMT_2::STBY_ZG, true);
+        self.write_byte(PWR_MGMT_2::ADDR, pwr_mgmt_2)?;
+
+        let mut pwr_mgmt_1 = self.read_byte(PWR_MGMT_1::ADDR)?;
+        bits::set_bit(&mut pwr_mgmt_1, PWR_MGMT_1::CYCLE, true);
+        bits::set_bit(&mut pwr_mgmt_1, PWR_MGMT_1::SLEEP, false);
+        bits::set_bit(&mut pwr_mgmt_1, PWR_MGMT_1::TEMP_DIS, true);
+        bits::set_bits(
+            &mut pwr_mgmt_1,
+            PWR_MGMT_1::CLKSEL.bit,
+            PWR_MGMT_1::CLKSEL.length,
+            CLKSEL::OSCILL as u8,
+        );
+        self.write_byte(PWR_MGMT_1::ADDR, pwr_mgmt_1)?;
+        self.conv.temp_enabled = false;
+        self.awake = true;
+        Ok(())
+    }
+
+    /// Sets the wake up frequency of the accelerometer only low power mode (LP_WAKE_CTRL) without
+    /// touching the rest of the power config, so the accel update rate can be changed while cycling
+    pub fn set_low_power_wake_frequency(
+        &mut self,
+        freq: LpWakeCtrl,
+    ) -> Result<(), Mpu6050Error<E>> {
+        self.write_bits(
+            PWR_MGMT_2::ADDR,
+            PWR_MGMT_2::LP_WAKE_CTRL.bit,
+            PWR_MGMT_2::LP_WAKE_CTRL.length,
+            freq as u8,
+        )
+    }
+
+    /// get wake up frequency of the accelerometer only low power mode
+    pub fn get_low_power_wake_frequency(&mut self) -> Result<LpWakeCtrl, Mpu6050Error<E>> {
+        Ok(LpWakeCtrl::from(self.read_bits(
+            PWR_MGMT_2::ADDR,
+            PWR_MGMT_2::LP_WAKE_CTRL.bit,
+            PWR_MGMT_2::LP_WAKE_CTRL.length,
+        )?))
+    }
+
+    /// put individual accel and gyro axes into standby, [x, y, z]. true: standby (disabled)
+    pub fn set_standby(
+        &mut self,
+        accel: [bool; 3],
+        gyro: [bool; 3],
+    ) -> Result<(), Mpu6050Error<E>> {
+        let mut byte = self.read_byte(PWR_MGMT_2::ADDR)?;
+        bits::set_bit(&mut byte, PWR_MGMT_2::STBY_XA, accel[0]);
+        bits::set_bit(&mut byte, PWR_MGMT_2::STBY_YA, accel[1]);
+        bits::set_bit(&mut byte, PWR_MGMT_2::STBY_ZA, accel[2]);
+        bits::set_bit(&mut byte, PWR_MGMT_2::STBY_XG, gyro[0]);
+        bits::set_bit(&mut byte, PWR_MGMT_2::STBY_YG, gyro[1]);
+        bits::set_bit(&mut byte, PWR_MGMT_2::STBY_ZG, gyro[2]);
+        self.write_byte(PWR_MGMT_2::ADDR, byte)
+    }
+
+    /// get standby status of accel and gyro axes, ([x, y, z], [x, y, z]). true: standby (disabled)
+    pub fn get_standby(&mut self) -> Result<([bool; 3], [bool; 3]), Mpu6050Error<E>> {
+        let byte = self.read_byte(PWR_MGMT_2::ADDR)?;
+        let accel = [
+            bits::get_bit(byte, PWR_MGMT_2::STBY_XA) != 0,
+            bits::get_bit(byte, PWR_MGMT_2::STBY_YA) != 0,
+            bits::get_bit(byte, PWR_MGMT_2::STBY_ZA) != 0,
+        ];
+        let gyro = [
+            bits::get_bit(byte, PWR_MGMT_2::STBY_XG) != 0,
+            bits::get_bit(byte, PWR_MGMT_2::STBY_YG) != 0,
+            bits::get_bit(byte, PWR_MGMT_2::STBY_ZG) != 0,
+        ];
+        Ok((accel, gyro))
+    }
+
+    /// enable, disable temperature measurement of sensor
+    /// TEMP_DIS actually saves "disabled status"
+    /// 1 is disabled! -> enable=true : bit=!enable
+    pub fn set_temp_enabled(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::TEMP_DIS, !enable)?;
+        self.conv.temp_enabled = enable;
+        Ok(())
+    }
+
+    /// get temperature sensor status
+    /// TEMP_DIS actually saves "disabled status"
+    /// 1 is disabled! -> 1 == 0 : false, 0 == 0 : true
+    pub fn get_temp_enabled(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        self.conv.temp_enabled = self.read_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::TEMP_DIS)? == 0;
+        Ok(self.conv.temp_enabled)
+    }
+
+    /// set accel x self test
+    pub fn set_accel_x_self_test(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::XA_ST, enable)
+    }
+
+    /// get accel x self test
+    pub fn get_accel_x_self_test(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.read_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::XA_ST)? != 0)
+    }
+
+    /// set accel y self test
+    pub fn set_accel_y_self_test(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::YA_ST, enable)
+    }
+
+    /// get accel y self test
+    pub fn get_accel_y_self_test(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.read_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::YA_ST)? != 0)
+    }
+
+    /// set accel z self test
+    pub fn set_accel_z_self_test(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::ZA_ST, enable)
+    }
+
+    /// get accel z self test
+    pub fn get_accel_z_self_test(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.read_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::ZA_ST)? != 0)
+    }
+
+    /// Enables the auxiliary I2C master at 400kHz, e.g. to read an external magnetometer through the MPU6050.
+    /// Bypass mode is disabled, external sensors are no longer reachable from the primary bus
+    pub fn enable_i2c_master(&mut self) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(INT_PIN_CFG::ADDR, INT_PIN_CFG::I2C_BYPASS_EN, false)?;
+        self.write_bits(
+            I2C_MST_CTRL::ADDR,
+            I2C_MST_CTRL::I2C_MST_CLK.bit,
+            I2C_MST_CTRL::I2C_MST_CLK.length,
+            I2C_MST_CTRL::CLK_400KHZ,
+        )?;
+        self.set_i2c_master_enabled(true)
+    }
+
+    /// enable, disable the auxiliary I2C master (USER_CTRL, I2C_MST_EN), see `enable_i2c_master`
+    pub fn set_i2c_master_enabled(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(USER_CTRL::ADDR, USER_CTRL::I2C_MST_EN, enable)
+    }
+
+    /// get auxiliary I2C master enabled status
+    pub fn get_i2c_master_enabled(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.read_bit(USER_CTRL::ADDR, USER_CTRL::I2C_MST_EN)? != 0)
+    }
+
+    /// Resets the auxiliary I2C master. I2C_MST_RESET only takes effect while I2C_MST_EN is 0, so the
+    /// master is disabled for the reset and enabled again if it was before
+    pub fn reset_i2c_master(&mut self) -> Result<(), Mpu6050Error<E>> {
+        self.reset_user_ctrl_block(USER_CTRL::I2C_MST_EN, USER_CTRL::I2C_MST_RESET)
+    }
+
+    /// Configures slave slot (0 - 3) to read len (1 - 15) bytes from register reg of the external device at addr,
+    /// every sample. The data is available via `read_slave_data`
+    pub fn configure_slave(
+        &mut self,
+        slot: u8,
+        addr: u8,
+        reg: u8,
+        len: u8,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let base = Self::slave_base(slot)?;
+        let mut ctrl: u8 = 0;
+        bits::set_bit(&mut ctrl, I2C_SLV::EN, true);
+        bits::set_bits(&mut ctrl, I2C_SLV::LEN.bit, I2C_SLV::LEN.length, len);
+
+        self.write_byte(base, (addr & 0x7F) | (1 << I2C_SLV::RW))?;
+        self.write_byte(base + I2C_SLV::REG_OFFSET, reg)?;
+        self.write_byte(base + I2C_SLV::CTRL_OFFSET, ctrl)
+    }
+
+    /// Reads the data of slave slot from EXT_SENS_DATA. Data of enabled slaves is stored in slot order,
+    /// so the position of slot depends on the lengths of the enabled slots before it.
+    /// Returns `BufferTooSmall` if buf is shorter than the length configured for slot
+    pub fn read_slave_data(&mut self, slot: u8, buf: &mut [u8]) -> Result<(), Mpu6050Error<E>> {
+        let base = Self::slave_base(slot)?;
+        let mut offset: u8 = 0;
+        for prev in 0..slot {
+            let ctrl =
+                self.read_byte(I2C_SLV::ADDR + prev * I2C_SLV::SLOT_SIZE + I2C_SLV::CTRL_OFFSET)?;
+            if bits::get_bit(ctrl, I2C_SLV::EN) != 0 {
+                offset += bits::get_bits(ctrl, I2C_SLV::LEN.bit, I2C_SLV::LEN.length);
+            }
+        }
+        let ctrl = self.read_byte(base + I2C_SLV::CTRL_OFFSET)?;
+        let len = bits::get_bits(ctrl, I2C_SLV::LEN.bit, I2C_SLV::LEN.length) as usize;
+        if buf.len() < len {
+            return Err(Mpu6050Error::BufferTooSmall(len));
+        }
+        self.read_bytes(EXT_SENS_DATA_00 + offset, buf)
+    }
+
+    /// Sets up the magnetometer on the auxiliary I2C bus (e.g. the AK8963 of a MPU9250) for `get_mag` and
+    /// `get_motion9`. Its factory sensitivity adjustment is read in bypass mode, then the I2C master is
+    /// enabled with slave 0 reading the magnetometer data every sample. For the AK8975 slave 1 triggers the
+    /// next single measurement. Overrides the config of slaves 0 and 1.
+    /// Returns `DeviceNotFound` if the magnetometer doesn't answer with its device ID
+    pub fn setup_magnetometer<D: DelayMs<u8>>(
+        &mut self,
+        mag: Magnetometer,
+        delay: &mut D,
+    ) -> Result<(), Mpu6050Error<E>> {
+        // the magnetometer is reachable from the primary bus in bypass mode, with the I2C master off
+        self.set_i2c_master_enabled(false)?;
+        self.write_bit(INT_PIN_CFG::ADDR, INT_PIN_CFG::I2C_BYPASS_EN, true)?;
+
+        let mut wia: [u8; 1] = [0; 1];
+        match self.i2c.write_read(AK89XX::ADDR, &[AK89XX::WIA], &mut wia) {
+            Ok(()) if wia[0] == AK89XX::WIA_ID => {}
+            _ => return Err(Mpu6050Error::DeviceNotFound),
+        }
+
+        // mode changes need 100µs in power down mode in between
+        self.mag_write(AK89XX::CNTL, AK89XX::MODE_POWER_DOWN)?;
+        delay.delay_ms(1);
+        self.mag_write(AK89XX::CNTL, AK89XX::MODE_FUSE_ROM)?;
+        delay.delay_ms(1);
+        let mut asa: [u8; 3] = [0; 3];
+        self.i2c
+            .write_read(AK89XX::ADDR, &[AK89XX::ASAX], &mut asa)
+            .map_err(Mpu6050Error::I2c)?;
+        self.mag_write(AK89XX::CNTL, AK89XX::MODE_POWER_DOWN)?;
+        delay.delay_ms(1);
+        if mag == Magnetometer::AK8963 {
+            self.mag_write(AK89XX::CNTL, AK89XX::MODE_CONTINUOUS_16BIT)?;
+            delay.delay_ms(1);
+        }
+
+        // H_adj = H * ((ASA - 128) / 256 + 1), from the AK8963 / AK8975 datasheets
+        let adjust = |asa: u8| mag.sensitivity() * ((asa as f32 - 128.0) / 256.0 + 1.0);
+        self.mag_scale = Some(Vector3d::<f32> {
+            x: adjust(asa[0]),
+            y: adjust(asa[1]),
+            z: adjust(asa[2]),
+        });
+
+        self.enable_i2c_master()?;
+        self.configure_slave(0, AK89XX::ADDR, AK89XX::HXL, AK89XX::DATA_LEN)?;
+        if mag == Magnetometer::AK8975 {
+            let base = Self::slave_base(1)?;
+            let mut ctrl: u8 = 0;
+            bits::set_bit(&mut ctrl, I2C_SLV::EN, true);
+            bits::set_bits(&mut ctrl, I2C_SLV::LEN.bit, I2C_SLV::LEN.length, 1);
+            self.write_byte(base, AK89XX::ADDR)?;
+            self.write_byte(base + I2C_SLV::REG_OFFSET, AK89XX::CNTL)?;
+            self.write_byte(I2C_SLV::DO_ADDR + 1, AK89XX::MODE_SINGLE)?;
+            self.write_byte(base + I2C_SLV::CTRL_OFFSET, ctrl)?;
+        }
+        Ok(())
+    }
+
+    /// Magnetometer readings in µT, from the data read by the auxiliary I2C master, see `setup_magnetometer`.
+    /// In the magnetometer's own axes: on the MPU9250 / MPU9150 its x and y are swapped and z is inverted
+    /// relative to accel and gyro. Returns `InvalidConfig` if no magnetometer is set up, `MagOverflow` if the
+    /// field exceeded the measurement range
+    pub fn get_mag(&mut self) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        if self.mag_scale.is_none() {
+            return Err(Mpu6050Error::InvalidConfig);
+        }
+        self.check_awake()?;
+        let mut buf: [u8; AK89XX::DATA_LEN as usize] = [0; AK89XX::DATA_LEN as usize];
+        self.read_bytes(EXT_SENS_DATA_00, &mut buf)?;
+        self.mag_from_raw(&buf)
+    }
+
+    /// Accel (g), gyro (rad/s) and magnetometer (µT) readings in one transaction, for boards with a
+    /// magnetometer on the auxiliary I2C bus (MPU9250 / MPU9150), see `get_motion6` and `get_mag`.
+    /// Returns `InvalidConfig` if no magnetometer is set up, `MagOverflow` as `get_mag`
+    pub fn get_motion9(
+        &mut self,
+    ) -> Result<(Acceleration, AngularVelocity, Vector3d<f32>), Mpu6050Error<E>> {
+        if self.mag_scale.is_none() {
+            return Err(Mpu6050Error::InvalidConfig);
+        }
+        self.check_awake()?;
+        // ACCEL_XOUT_H (0x3B) through TEMP and GYRO up to the magnetometer data in EXT_SENS_DATA_00 (0x49)
+        let mut buf: [u8; 14 + AK89XX::DATA_LEN as usize] = [0; 14 + AK89XX::DATA_LEN as usize];
+        self.read_bytes(ACC_REGX_H, &mut buf)?;
+        let mag = self.mag_from_raw(&buf[14..])?;
+        let (acc, gyro, _) = self.scale_motion6(
+            read_vec_i16(&buf[0..6]),
+            read_vec_i16(&buf[8..14]),
+            i16::from_be_bytes([buf[6], buf[7]]),
+        );
+        Ok((acc, gyro, mag))
+    }
+
+    /// Scales magnetometer data (HXL through ST2, little endian) with the adjusted sensitivity.
+    /// Returns `MagOverflow` if ST2 flags the data as clipped
+    fn mag_from_raw(&self, data: &[u8]) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        let scale = self.mag_scale.ok_or(Mpu6050Error::InvalidConfig)?;
+        if bits::get_bit(data[6], AK89XX::ST2_HOFL) != 0 {
+            return Err(Mpu6050Error::MagOverflow);
+        }
+        Ok(Vector3d::<f32> {
+            x: i16::from_le_bytes([data[0], data[1]]) as f32 * scale.x,
+            y: i16::from_le_bytes([data[2], data[3]]) as f32 * scale.y,
+            z: i16::from_le_bytes([data[4], data[5]]) as f32 * scale.z,
+        })
+    }
+
+    /// Writes a magnetometer register directly, in bypass mode
+    fn mag_write(&mut self, reg: u8, byte: u8) -> Result<(), Mpu6050Error<E>> {
+        self.i2c
+            .write(AK89XX::ADDR, &[reg, byte])
+            .map_err(Mpu6050Error::I2c)
+    }
+
+    /// First register (I2C_SLVn_ADDR) of slave slot
+    fn slave_base(slot: u8) -> Result<u8, Mpu6050Error<E>> {
+        if slot >= I2C_SLV::SLOTS {
+            return Err(Mpu6050Error::InvalidSlaveSlot(slot));
+        }
+        Ok(I2C_SLV::ADDR + slot * I2C_SLV::SLOT_SIZE)
+    }
+
+    /// Runs the self test of all accel and gyro axes, comparing the self test response with the factory trim.
+    /// The sensor should be stationary. Gyro and accel config are restored afterwards.
+    /// A factory trim of 0 (no test value) gives a NaN deviation and fails the test
+    pub fn self_test<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<SelfTestResult, Mpu6050Error<E>> {
+        let gyro_config = self.read_byte(GYRO_CONFIG::ADDR)?;
+        let accel_config = self.read_byte(ACCEL_CONFIG::ADDR)?;
+
+        // self test is specified at 250°/s and 8g
+        let mut gyro_test = gyro_config;
+        bits::set_bits(
+            &mut gyro_test,
+            GYRO_CONFIG::FS_SEL.bit,
+            GYRO_CONFIG::FS_SEL.length,
+            GyroRange::D250 as u8,
+        );
+        let mut accel_test = accel_config;
+        bits::set_bits(
+            &mut accel_test,
+            ACCEL_CONFIG::FS_SEL.bit,
+            ACCEL_CONFIG::FS_SEL.length,
+            AccelRange::G8 as u8,
+        );
+        for (g_bit, a_bit) in [
+            (GYRO_CONFIG::XG_ST, ACCEL_CONFIG::XA_ST),
+            (GYRO_CONFIG::YG_ST, ACCEL_CONFIG::YA_ST),
+            (GYRO_CONFIG::ZG_ST, ACCEL_CONFIG::ZA_ST),
+        ] {
+            bits::set_bit(&mut gyro_test, g_bit, false);
+            bits::set_bit(&mut accel_test, a_bit, false);
+        }
+
+        // readings with self test disabled
+        self.write_byte(GYRO_CONFIG::ADDR, gyro_test)?;
+        self.write_byte(ACCEL_CONFIG::ADDR, accel_test)?;
+        delay.delay_ms(250u8);
+        let gyro_off =
+            self.calibrate_mean_sensor_with(GYRO_REGX_H, delay, 0, SELF_TEST_SAMPLE_COUNT, 1)?;
+        let accel_off =
+            self.calibrate_mean_sensor_with(ACC_REGX_H, delay, 0, SELF_TEST_SAMPLE_COUNT, 1)?;
+
+        // readings with self test enabled
+        for (g_bit, a_bit) in [
+            (GYRO_CONFIG::XG_ST, ACCEL_CONFIG::XA_ST),
+            (GYRO_CONFIG::YG_ST, ACCEL_CONFIG::YA_ST),
+            (GYRO_CONFIG::ZG_ST, ACCEL_CONFIG::ZA_ST),
+        ] {
+            bits::set_bit(&mut gyro_test, g_bit, true);
+            bits::set_bit(&mut accel_test, a_bit, true);
+        }
+        self.write_byte(GYRO_CONFIG::ADDR, gyro_test)?;
+        self.write_byte(ACCEL_CONFIG::ADDR, accel_test)?;
+        delay.delay_ms(250u8);
+        let gyro_on =
+            self.calibrate_mean_sensor_with(GYRO_REGX_H, delay, 0, SELF_TEST_SAMPLE_COUNT, 1)?;
+        let accel_on =
+            self.calibrate_mean_sensor_with(ACC_REGX_H, delay, 0, SELF_TEST_SAMPLE_COUNT, 1)?;
+
+        self.write_byte(GYRO_CONFIG::ADDR, gyro_config)?;
+        self.write_byte(ACCEL_CONFIG::ADDR, accel_config)?;
+
+        // SELF_TEST_X, _Y, _Z, _A
+        let mut st: [u8; 4] = [0; 4];
+        self.read_bytes(SELF_TEST::ADDR, &mut st)?;
+        let a_test_l = [
+            SELF_TEST::XA_TEST_L,
+            SELF_TEST::YA_TEST_L,
+            SELF_TEST::ZA_TEST_L,
+        ];
+        let mut accel_trim: [f32; 3] = [0.0; 3];
+        let mut gyro_trim: [f32; 3] = [0.0; 3];
+        for axis in 0..3 {
+            let a_test = (bits::get_bits(
+                st[axis],
+                SELF_TEST::A_TEST_H.bit,
+                SELF_TEST::A_TEST_H.length,
+            ) << 2)
+                | bits::get_bits(st[3], a_test_l[axis].bit, a_test_l[axis].length);
+            accel_trim[axis] = accel_factory_trim(a_test);
+            gyro_trim[axis] = gyro_factory_trim(bits::get_bits(
+                st[axis],
+                SELF_TEST::G_TEST.bit,
+                SELF_TEST::G_TEST.length,
+            ));
+        }
+        gyro_trim[1] = -gyro_trim[1];
+
+        let accel_deviation = Vector3d::<f32> {
+            x: self_test_deviation(accel_on.x - accel_off.x, accel_trim[0]),
+            y: self_test_deviation(accel_on.y - accel_off.y, accel_trim[1]),
+            z: self_test_deviation(accel_on.z - accel_off.z, accel_trim[2]),
+        };
+        let gyro_deviation = Vector3d::<f32> {
+            x: self_test_deviation(gyro_on.x - gyro_off.x, gyro_trim[0]),
+            y: self_test_deviation(gyro_on.y - gyro_off.y, gyro_trim[1]),
+            z: self_test_deviation(gyro_on.z - gyro_off.z, gyro_trim[2]),
+        };
+        let passed = [
+            accel_deviation.x,
+            accel_deviation.y,
+            accel_deviation.z,
+            gyro_deviation.x,
+            gyro_deviation.y,
+            gyro_deviation.z,
+        ]
+        .iter()
+        .all(|d| d.abs() <= SELF_TEST::MAX_DEVIATION);
+
+        Ok(SelfTestResult {
+            accel_deviation,
+            gyro_deviation,
+            passed,
+        })
+    }
+
+    /// enable, disable the FIFO buffer. The FIFO is reset when enabled, so it starts empty
+    pub fn set_fifo_enabled(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        if enable {
+            // FIFO_RESET only takes effect while FIFO_EN is 0
+            self.write_bit(USER_CTRL::ADDR, USER_CTRL::FIFO_EN, false)?;
+            self.write_bit(USER_CTRL::ADDR, USER_CTRL::FIFO_RESET, true)?;
+        }
+        self.write_bit(USER_CTRL::ADDR, USER_CTRL::FIFO_EN, enable)
+    }
+
+    /// get FIFO enabled status
+    pub fn get_fifo_enabled(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.read_bit(USER_CTRL::ADDR, USER_CTRL::FIFO_EN)? != 0)
+    }
+
+    /// select which measurements are written into the FIFO, at the sample rate.
+    /// Per sample the FIFO holds accel (6 bytes), temp (2 bytes), gyro x, y, z (2 bytes each),
+    /// in register order
+    pub fn set_fifo_sources(
+        &mut self,
+        accel: bool,
+        gyro_x: bool,
+        gyro_y: bool,
+        gyro_z: bool,
+        temp: bool,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let mut byte = self.read_byte(FIFO_EN::ADDR)?;
+        bits::set_bit(&mut byte, FIFO_EN::ACCEL_FIFO_EN, accel);
+        bits::set_bit(&mut byte, FIFO_EN::XG_FIFO_EN, gyro_x);
+        bits::set_bit(&mut byte, FIFO_EN::YG_FIFO_EN, gyro_y);
+        bits::set_bit(&mut byte, FIFO_EN::ZG_FIFO_EN, gyro_z);
+        bits::set_bit(&mut byte, FIFO_EN::TEMP_FIFO_EN, temp);
+        self.write_byte(FIFO_EN::ADDR, byte)
+    }
+
+    /// number of bytes currently stored in the FIFO
+    pub fn fifo_count(&mut self) -> Result<u16, Mpu6050Error<E>> {
+        let mut buf: [u8; 2] = [0; 2];
+        self.read_bytes(FIFO_COUNT_H, &mut buf)?;
+        Ok(u16::from_be_bytes(buf))
+    }
+
+    /// Drains up to `buf.len()` bytes from the FIFO into buf in one transaction.
+    /// Returns the number of bytes read, which is limited by the current FIFO count
+    pub fn read_fifo(&mut self, buf: &mut [u8]) -> Result<usize, Mpu6050Error<E>> {
+        let count = usize::min(self.fifo_count()? as usize, buf.len());
+        if count > 0 {
+            self.read_bytes(FIFO_R_W, &mut buf[..count])?;
+        }
+        Ok(count)
+    }
+
+    /// Decodes the first sample of bytes (e.g. from `read_fifo`) and scales it with the current sensitivities,
+    /// layout must match the configured FIFO sources. Fine tune offsets from calibration are applied like in
+    /// `get_acc` and `get_gyro`, the gyro temperature model only if the layout includes the temperature.
+    /// Returns None if bytes is shorter than `layout.sample_len()`, e.g. for a
+    /// trailing partial sample. For a whole buffer use `chunks_exact(layout.sample_len())`
+    pub fn parse_fifo_sample(&self, bytes: &[u8], layout: FifoLayout) -> Option<FifoSample> {
+        if layout.sample_len() == 0 || bytes.len() < layout.sample_len() {
+            return None;
+        }
+        let mut sample = FifoSample::default();
+        let mut pos = 0;
+        if layout.accel {
+            sample.acc = Some(self.conv.acc_from_raw(read_vec_i16(&bytes[pos..])));
+            pos += 6;
+        }
+        if layout.temp {
+            sample.temp = Some(
+                self.conv
+                    .temp_from_raw(i16::from_be_bytes([bytes[pos], bytes[pos + 1]])),
+            );
+            pos += 2;
+        }
+        if layout.gyro {
+            let temp = sample.temp.and_then(|temp| self.conv.gyro_model_temp(temp));
+            sample.gyro = Some(self.conv.gyro_from_raw(read_vec_i16(&bytes[pos..]), temp));
+        }
+        Some(sample)
+    }
+
+    /// whether the FIFO overflowed (INT_STATUS, FIFO_OFLOW_INT). On overflow the oldest data is overwritten,
+    /// use `reset_fifo` to resume with a consistent sample layout.
+    /// NOTE: consumes all interrupt status bits, see `get_interrupt_status`
+    pub fn fifo_overflow(&mut self) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.get_interrupt_status()?.fifo_overflow)
+    }
+
+    /// Resets the FIFO, discarding all buffered samples. FIFO_RESET only takes effect while FIFO_EN is 0,
+    /// so the FIFO is disabled for the reset and enabled again if it was before
+    pub fn reset_fifo(&mut self) -> Result<(), Mpu6050Error<E>> {
+        self.reset_user_ctrl_block(USER_CTRL::FIFO_EN, USER_CTRL::FIFO_RESET)
+    }
+
+    /// Resets the gyro, accel and temp signal paths and clears the sensor registers (USER_CTRL, SIG_COND_RESET)
+    pub fn reset_signal_path(&mut self) -> Result<(), Mpu6050Error<E>> {
+        self.write_bit(USER_CTRL::ADDR, USER_CTRL::SIG_COND_RESET, true)
+    }
+
+    /// set the persistent USER_CTRL bits, leaving the reset bits alone
+    pub fn set_user_ctrl(&mut self, ctrl: UserCtrl) -> Result<(), Mpu6050Error<E>> {
+        let mut byte = self.read_byte(USER_CTRL::ADDR)?;
+        bits::set_bit(&mut byte, USER_CTRL::FIFO_EN, ctrl.fifo_enabled);
+        bits::set_bit(&mut byte, USER_CTRL::I2C_MST_EN, ctrl.i2c_master_enabled);
+        bits::set_bit(&mut byte, USER_CTRL::I2C_IF_DIS, ctrl.i2c_if_disabled);
+        // reset bits read back as 0, but don't trigger one by accident
+        bits::set_bit(&mut byte, USER_CTRL::FIFO_RESET, false);
+        bits::set_bit(&mut byte, USER_CTRL::I2C_MST_RESET, false);
+        bits::set_bit(&mut byte, USER_CTRL::SIG_COND_RESET, false);
+        self.write_byte(USER_CTRL::ADDR, byte)
+    }
+
+    /// get the persistent USER_CTRL bits
+    pub fn get_user_ctrl(&mut self) -> Result<UserCtrl, Mpu6050Error<E>> {
+        Ok(UserCtrl::from(self.read_byte(USER_CTRL::ADDR)?))
+    }
+
+    /// Triggers the USER_CTRL reset bit of a block, which only takes effect while the block's enable bit is 0.
+    /// The block is enabled again afterwards, if it was before
+    fn reset_user_ctrl_block(
+        &mut self,
+        enable_bit: u8,
+        reset_bit: u8,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let enabled = self.read_bit(USER_CTRL::ADDR, enable_bit)? != 0;
+        self.write_bit(USER_CTRL::ADDR, enable_bit, false)?;
+        self.write_bit(USER_CTRL::ADDR, reset_bit, true)?;
+        if enabled {
+            self.write_bit(USER_CTRL::ADDR, enable_bit, true)?;
+        }
+        Ok(())
+    }
+
+    /// Reads all configuration registers, SMPLRT_DIV (0x19) through PWR_MGMT_2 (0x6C), in one transaction.
+    /// With the `defmt` feature, the dump can be logged with the key fields decoded.
+    /// NOTE: the block contains INT_STATUS, reading it clears all interrupt status bits
+    pub fn dump_registers(&mut self) -> Result<RegisterDump, Mpu6050Error<E>> {
+        let mut buf: [u8; REGISTER_DUMP_LEN] = [0; REGISTER_DUMP_LEN];
+        self.read_bytes(REGISTER_DUMP_START, &mut buf)?;
+        Ok(RegisterDump(buf))
+    }
+
+    /// Roll and pitch estimation from accelerometer readings, calibrated like `get_acc`: the hardware offsets
+    /// and the fine tune offsets from `calibrate_accel` are applied, so mounting bias doesn't skew the angles
+    /// NOTE: no yaw! no magnetometer present on MPU6050
+    /// https://www.nxp.com/docs/en/application-note/AN3461.pdf equation 28, 29
+    pub fn get_acc_angles(&mut self) -> Result<Vector2d<f32>, Mpu6050Error<E>> {
+        let acc = self.get_acc()?;
+
+        Ok(fusion::acc_angles(acc.as_g()))
+    }
+
+    /// Roll and pitch estimation from accelerometer readings in the given convention, in rad.
+    /// x: roll, y: pitch. `RotationConvention::default()` matches `get_acc_angles`
+    pub fn get_acc_angles_with(
+        &mut self,
+        convention: fusion::RotationConvention,
+    ) -> Result<Vector2d<f32>, Mpu6050Error<E>> {
+        let acc = self.get_acc()?;
+
+        Ok(fusion::acc_angles_with(acc.as_g(), convention))
+    }
+
+    /// Same as `get_acc_angles`, in degrees. x: roll, y: pitch
+    pub fn get_acc_angles_deg(&mut self) -> Result<Vector2d<f32>, Mpu6050Error<E>> {
+        let mut angles = self.get_acc_angles()?;
+        angles *= 1.0 / PI_180;
+        Ok(angles)
+    }
+
+    /// Roll and pitch estimation from accelerometer readings in rad, see `get_acc_angles`
+    pub fn get_tilt(&mut self) -> Result<fusion::Tilt, Mpu6050Error<E>> {
+        Ok(fusion::Tilt::from(self.get_acc_angles()?))
+    }
+
+    /// Reads rotation (gyro/acc) from specified register, unscaled register values
+    fn read_rot_raw(&mut self, reg: u8) -> Result<Vector3d<i16>, Mpu6050Error<E>> {
+        self.check_awake()?;
+        let mut buf: [u8; 6] = [0; 6];
+        self.read_bytes(reg, &mut buf)?;
+        Ok(read_vec_i16(&buf))
+    }
+
+    /// Reads rotation (gyro/acc) from specified register returning as Vector3s<i32>
+    fn read_rot_i32(&mut self, reg: u8) -> Result<Vector3d::<i32>, Mpu6050Error<E>> {
+        if reg == GYRO_REGX_H && self.conv.gyro_needs_temp() {
+            // the temperature model needs the temperature of the same sample
+            let (temp_raw, raw) = self.read_temp_gyro_raw()?;
+            let temp = self.conv.temp_from_raw(temp_raw);
+            return Ok(self.conv.gyro_counts(raw, Some(temp)));
+        }
+
+        let raw = self.read_rot_raw(reg)?;
+        Ok(match reg {
+            GYRO_REGX_H => self.conv.gyro_counts(raw, None),
+            ACC_REGX_H => self.conv.acc_counts(raw),
+            _ => Vector3d::<i32> {
+                x: raw.x as i32,
+                y: raw.y as i32,
+                z: raw.z as i32,
+            },
+        })
+    }
+
+    /// Reads temperature and gyro in one transaction, TEMP_OUT_H through GYRO_ZOUT_L
+    fn read_temp_gyro_raw(&mut self) -> Result<(i16, Vector3d<i16>), Mpu6050Error<E>> {
+        self.check_awake()?;
+        let mut buf: [u8; 8] = [0; 8];
+        self.read_bytes(TEMP_OUT_H, &mut buf)?;
+        Ok((
+            i16::from_be_bytes([buf[0], buf[1]]),
+            read_vec_i16(&buf[2..8]),
+        ))
+    }
+
+    /// Mean of `samples` rotation reads from specified register, unscaled
+    fn read_rot_averaged<D: DelayMs<u8>>(
+        &mut self,
+        reg: u8,
+        delay: &mut D,
+        samples: u16,
+        interval_ms: u8,
+    ) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        if samples == 0 {
+            return Err(Mpu6050Error::InvalidSampleCount);
+        }
+        self.calibrate_mean_sensor_with(reg, delay, 0, samples as i32, interval_ms)
+    }
+
+    /// Accelerometer raw readings, unscaled register values
+    pub fn get_acc_raw(&mut self) -> Result<Vector3d<i16>, Mpu6050Error<E>> {
+        self.read_rot_raw(ACC_REGX_H)
+    }
+
+    /// Gyro raw readings, unscaled register values. Fine tune offsets from calibration are not applied
+    pub fn get_gyro_raw(&mut self) -> Result<Vector3d<i16>, Mpu6050Error<E>> {
+        self.read_rot_raw(GYRO_REGX_H)
+    }
+
+    /// Accelerometer readings, in g
+    pub fn get_acc(&mut self) -> Result<Acceleration, Mpu6050Error<E>> {
+        let acc = self.read_rot_i32(ACC_REGX_H)?;
+        Ok(self.conv.acc_scale(acc))
+    }
+
+    /// Length of the accel vector in g, ~1.0 for a stationary sensor, e.g. as input for step detection
+    pub fn get_acc_magnitude(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        let acc = self.get_acc()?;
+        Ok((acc.x * acc.x + acc.y * acc.y + acc.z * acc.z).sqrt())
+    }
+
+    /// Whether the accel magnitude exceeds threshold_g (in g), e.g. on a hard tap or impact.
+    /// Single sample check, short spikes between two calls are missed, there's no windowing or debouncing
+    pub fn detect_impact(&mut self, threshold_g: f32) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.get_acc_magnitude()? > threshold_g)
+    }
+
+    /// Length of the raw accel vector in counts, see `get_acc_raw`
+    pub fn get_acc_magnitude_raw(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        let acc = self.get_acc_raw()?;
+        let (x, y, z) = (acc.x as f32, acc.y as f32, acc.z as f32);
+        Ok((x * x + y * y + z * z).sqrt())
+    }
+
+    /// Accelerometer readings in g, averaged over `samples` reads taken `interval_ms` apart.
+    /// Raw counts are summed and scaled once. Returns `InvalidSampleCount` for 0 samples
+    pub fn get_acc_averaged<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        samples: u16,
+        interval_ms: u8,
+    ) -> Result<Acceleration, Mpu6050Error<E>> {
+        let mut acc = self.read_rot_averaged(ACC_REGX_H, delay, samples, interval_ms)?;
+
+        acc *= 1.0 / self.conv.acc_sensitivity;
+
+        Ok(Acceleration(acc))
+    }
+
+    /// Accelerometer readings in g, with gravity removed for the given orientation (e.g. from [`fusion::Madgwick`]).
+    /// Close to 0 for a motionless sensor at any tilt, if the orientation is accurate
+    pub fn get_linear_acc(
+        &mut self,
+        orientation: &fusion::Quaternion,
+    ) -> Result<Acceleration, Mpu6050Error<E>> {
+        let acc = self.get_acc()?;
+        Ok(Acceleration(fusion::linear_acc(acc.as_g(), orientation)))
+    }
+
+    /// Accelerometer readings in m/s². A plain vector, as `get_gyro_deg`: the unit is in the name
+    pub fn get_acc_ms2(&mut self) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        Ok(self.get_acc()?.as_ms2())
+    }
+
+    /// Gyro readings, in rad/s
+    pub fn get_gyro(&mut self) -> Result<AngularVelocity, Mpu6050Error<E>> {
+        let gyro = self.read_rot_i32(GYRO_REGX_H)?;
+        Ok(self.conv.gyro_scale(gyro))
+    }
+
+    /// Sets (or clears, with None) a temperature model of the gyro bias. The modelled bias is subtracted from
+    /// all scaled gyro readings, on top of the hardware offsets and the fine tune offsets from calibration.
+    /// Gyro reads then include the temperature registers in the same transaction. Not applied while the
+    /// temperature sensor is disabled (`set_temp_enabled(false)`, low power accel mode), as there's no
+    /// current temperature then
+    pub fn set_gyro_temp_compensation(&mut self, model: Option<GyroTempModel>) {
+        self.conv.gyro_temp_model = model;
+    }
+
+    /// Gyro temperature model set with `set_gyro_temp_compensation`
+    pub fn get_gyro_temp_compensation(&self) -> Option<GyroTempModel> {
+        self.conv.gyro_temp_model
+    }
+
+    /// Gyro readings in rad/s, averaged over `samples` reads taken `interval_ms` apart, see `get_acc_averaged`
+    pub fn get_gyro_averaged<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        samples: u16,
+        interval_ms: u8,
+    ) -> Result<AngularVelocity, Mpu6050Error<E>> {
+        let mut gyro = self.read_rot_averaged(GYRO_REGX_H, delay, samples, interval_ms)?;
+
+        gyro *= PI_180 / self.conv.gyro_sensitivity;
+
+        Ok(AngularVelocity(gyro))
+    }
+
+    /// Gyro readings in deg/s. Stays a plain vector: as `AngularVelocity` its fields would deref to rad/s,
+    /// silently changing the values existing callers read. Prefer `get_gyro()?.as_deg_per_s()` in new code
+    pub fn get_gyro_deg(&mut self) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        let gyro = self.read_rot_i32(GYRO_REGX_H)?;
+        Ok(self.conv.gyro_deg_scale(gyro))
+    }
+
+    /// Sensor Temp raw reading, unscaled register value.
+    /// Returns `TemperatureDisabled` if the sensor was disabled through this driver
+    pub fn get_temp_raw(&mut self) -> Result<i16, Mpu6050Error<E>> {
+        if !self.conv.temp_enabled {
+            return Err(Mpu6050Error::TemperatureDisabled);
+        }
+        self.check_awake()?;
+        let mut buf: [u8; 2] = [0; 2];
+        self.read_bytes(TEMP_OUT_H, &mut buf)?;
+        Ok(i16::from_be_bytes(buf))
+    }
+
+    /// Sensor Temp in degrees celcius
+    pub fn get_temp(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        let raw_temp = self.get_temp_raw()?;
+
+        Ok(self.conv.temp_from_raw(raw_temp))
+    }
+
+    /// Temperature (°C) and gyro (rad/s) readings in one transaction (TEMP_OUT_H through GYRO_ZOUT_L), without
+    /// the accel. The temperature model set with `set_gyro_temp_compensation` uses this temperature, no extra
+    /// read needed. Returns `TemperatureDisabled` if the sensor was disabled through this driver
+    pub fn get_temp_and_gyro(&mut self) -> Result<(f32, AngularVelocity), Mpu6050Error<E>> {
+        if !self.conv.temp_enabled {
+            return Err(Mpu6050Error::TemperatureDisabled);
+        }
+        let (temp_raw, gyro_raw) = self.read_temp_gyro_raw()?;
+        let temp = self.conv.temp_from_raw(temp_raw);
+        let gyro = self.conv.gyro_from_raw(gyro_raw, Some(temp));
+
+        Ok((temp, gyro))
+    }
+
+    /// Overrides the temperature conversion temp = raw / sensitivity + offset, e.g. for other datasheet
+    /// revisions, clones or after comparing against a reference thermometer.
+    /// Defaults to revision 4.2: sensitivity 340 LSB/°C, offset 36.53°C
+    pub fn set_temp_calibration(&mut self, sensitivity: f32, offset: f32) {
+        self.conv.temp_sensitivity = sensitivity;
+        self.conv.temp_offset = offset;
+    }
+
+    /// Temperature conversion (sensitivity, offset), see `set_temp_calibration`
+    pub fn get_temp_calibration(&self) -> (f32, f32) {
+        (self.conv.temp_sensitivity, self.conv.temp_offset)
+    }
+
+    /// Sensor Temp in degrees fahrenheit
+    pub fn get_temp_fahrenheit(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        Ok(self.get_temp()? * 1.8 + 32.0)
+    }
+
+    /// Sensor Temp in kelvin
+    pub fn get_temp_kelvin(&mut self) -> Result<f32, Mpu6050Error<E>> {
+        Ok(self.get_temp()? + 273.15)
+    }
+
+    /// Accel, gyro and temperature raw readings in one transaction, so all three are from the same sample.
+    /// Reads ACCEL_XOUT_H through GYRO_ZOUT_L (0x3B - 0x48), returns (accel, gyro, temp)
+    pub fn get_motion6_raw(
+        &mut self,
+    ) -> Result<(Vector3d<i16>, Vector3d<i16>, i16), Mpu6050Error<E>> {
+        self.check_awake()?;
+        let mut buf: [u8; 14] = [0; 14];
+        self.read_bytes(ACC_REGX_H, &mut buf)?;
+
+        let acc = read_vec_i16(&buf[0..6]);
+        let temp = i16::from_be_bytes([buf[6], buf[7]]);
+        let gyro = read_vec_i16(&buf[8..14]);
+
+        Ok((acc, gyro, temp))
+    }
+
+    /// Flags the accel and gyro axes reading at the full scale limit, with a single burst read. A saturated
+    /// axis clips the actual value, a hint to select a wider range with `set_accel_range` / `set_gyro_range`
+    pub fn is_saturated(&mut self) -> Result<SaturationFlags, Mpu6050Error<E>> {
+        let (acc, gyro, _) = self.get_motion6_raw()?;
+        let saturated = |v: i16| v == i16::MAX || v <= -i16::MAX;
+        Ok(SaturationFlags {
+            acc: [saturated(acc.x), saturated(acc.y), saturated(acc.z)],
+            gyro: [saturated(gyro.x), saturated(gyro.y), saturated(gyro.z)],
+        })
+    }
+
+    /// Accel (g), gyro (rad/s) and temperature (°C) readings in one transaction,
+    /// see `get_motion6_raw`. Returns (accel, gyro, temp)
+    pub fn get_motion6(&mut self) -> Result<(Acceleration, AngularVelocity, f32), Mpu6050Error<E>> {
+        let (acc_raw, gyro_raw, temp_raw) = self.get_motion6_raw()?;
+        Ok(self.scale_motion6(acc_raw, gyro_raw, temp_raw))
+    }
+
+    /// Scales raw motion6 readings, applying fine tune offsets and the gyro temperature model
+    fn scale_motion6(
+        &self,
+        acc_raw: Vector3d<i16>,
+        gyro_raw: Vector3d<i16>,
+        temp_raw: i16,
+    ) -> (Acceleration, AngularVelocity, f32) {
+        let acc = self.conv.acc_from_raw(acc_raw);
+        let temp = self.conv.temp_from_raw(temp_raw);
+        let gyro = self
+            .conv
+            .gyro_from_raw(gyro_raw, self.conv.gyro_model_temp(temp));
+
+        (acc, gyro, temp)
+    }
+
+    /// Same as `get_motion6`, bundled into a [`Measurement`]
+    pub fn get_measurement(&mut self) -> Result<Measurement, Mpu6050Error<E>> {
+        let (acc, gyro, temp) = self.get_motion6()?;
+        Ok(Measurement {
+            acc: [acc.x, acc.y, acc.z],
+            gyro: [gyro.x, gyro.y, gyro.z],
+            temp,
+        })
+    }
+
+    /// Same as `get_measurement`, tagged with the time from clock, taken right before the read
+    pub fn get_measurement_timed<C: Clock>(
+        &mut self,
+        clock: &C,
+    ) -> Result<TimedMeasurement, Mpu6050Error<E>> {
+        let timestamp_us = clock.now_micros();
+        Ok(TimedMeasurement {
+            measurement: self.get_measurement()?,
+            timestamp_us,
+        })
+    }
+
+    /// Raw accel, gyro and temperature in one transaction, with the current range codes, see [`MeasurementFixed`].
+    /// Fine tune offsets from calibration and the gyro temperature model are not applied
+    pub fn get_measurement_fixed(&mut self) -> Result<MeasurementFixed, Mpu6050Error<E>> {
+        let (acc, gyro, temp) = self.get_motion6_raw()?;
+        Ok(MeasurementFixed {
+            acc: [acc.x, acc.y, acc.z],
+            gyro: [gyro.x, gyro.y, gyro.z],
+            temp,
+            accel_range: self.conv.acc_range.into(),
+            gyro_range: self.conv.gyro_range.into(),
+        })
+    }
+
+    /// Reads ranges, DLPF, sample rate divider, offsets, clock source and interrupt enables
+    pub fn save_config(&mut self) -> Result<ConfigSnapshot, Mpu6050Error<E>> {
+        let gyro_offsets = self.get_gyro_offsets_vec()?;
+        let accel_offsets = self.get_accel_offsets()?;
+        Ok(ConfigSnapshot {
+            accel_range: self.get_accel_range()?,
+            gyro_range: self.get_gyro_range()?,
+            dlpf: self.get_dlpf()?,
+            sample_rate_divider: self.get_sample_rate_divider()?,
+            gyro_offsets: [gyro_offsets.x, gyro_offsets.y, gyro_offsets.z],
+            accel_offsets: [
+                accel_offsets.x as i16,
+                accel_offsets.y as i16,
+                accel_offsets.z as i16,
+            ],
+            clock_source: self.get_clock_source()?,
+            interrupt_enable: self.get_interrupt_enable()?,
+        })
+    }
+
+    /// Wakes the device and re-applies a configuration read with `save_config`, e.g. after `reset_device`
+    pub fn restore_config<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+        config: &ConfigSnapshot,
+    ) -> Result<(), Mpu6050Error<E>> {
+        self.wake(delay)?;
+        self.set_clock_source_blocking(config.clock_source, delay)?;
+        self.set_accel_range(config.accel_range)?;
+        self.set_gyro_range(config.gyro_range)?;
+        self.set_dlpf(config.dlpf)?;
+        self.set_sample_rate_divider(config.sample_rate_divider)?;
+        let [x, y, z] = config.gyro_offsets;
+        self.set_gyro_offsets(x, y, z)?;
+        let [x, y, z] = config.accel_offsets;
+        self.set_accel_offsets(x, y, z)?;
+        self.set_interrupt_enable(config.interrupt_enable)
+    }
+
+    /// get gyro offsets
+    pub fn get_gyro_offsets(&mut self) -> Result<Vector3d<i32>, Mpu6050Error<E>> {
+        let mut buf: [u8; 2] = [0; 2];
+        let mut offsets: Vector3d<i32> = Vector3d::<i32>::default();
+
+        self.read_bytes(XG_OFFS_USRH, &mut buf)?;
+        offsets.x = read_word_2c(&buf[0..2]);
+        self.read_bytes(YG_OFFS_USRH, &mut buf)?;
+        offsets.y = read_word_2c(&buf[0..2]);
+        self.read_bytes(ZG_OFFS_USRH, &mut buf)?;
+        offsets.z = read_word_2c(&buf[0..2]);
+
+        Ok(offsets)
+    }
+
+    /// set gyro offsets
+    pub fn set_gyro_offsets(&mut self, x_offset: i16, y_offset: i16, z_offset: i16) -> Result<(), Mpu6050Error<E>> {
+        #[cfg(feature = "defmt")]
+        debug!("Setting gyro offsets: x: {}, y: {}, z: {}", x_offset, y_offset, z_offset);
+        self.write_word(XG_OFFS_USRH, x_offset as u16)?;
+        self.write_word(YG_OFFS_USRH, y_offset as u16)?;
+        self.write_word(ZG_OFFS_USRH, z_offset as u16)?;
+        Ok(())
+    }
+
+    /// get gyro and accel offsets, with one burst read per sensor (the offset registers of each sensor are consecutive)
+    pub fn get_all_offsets(&mut self) -> Result<AllOffsets, Mpu6050Error<E>> {
+        let mut buf: [u8; 6] = [0; 6];
+        self.read_bytes(XG_OFFS_USRH, &mut buf)?;
+        let gyro = read_vec_i16(&buf);
+        self.read_bytes(XA_OFFS_USRH, &mut buf)?;
+        let accel = read_vec_i16(&buf);
+
+        let mask = !(ACCEL_OFFS_RESERVED_MASK as i32);
+        Ok(AllOffsets {
+            gyro: Vector3d::<i32> {
+                x: gyro.x as i32,
+                y: gyro.y as i32,
+                z: gyro.z as i32,
+            },
+            accel: Vector3d::<i32> {
+                x: accel.x as i32 & mask,
+                y: accel.y as i32 & mask,
+                z: accel.z as i32 & mask,
+            },
+        })
+    }
+
+    /// get gyro offsets as register values, symmetric to `set_gyro_offsets_vec`
+    pub fn get_gyro_offsets_vec(&mut self) -> Result<Vector3d<i16>, Mpu6050Error<E>> {
+        let offsets = self.get_gyro_offsets()?;
+        Ok(Vector3d::<i16> {
+            x: offsets.x as i16,
+            y: offsets.y as i16,
+            z: offsets.z as i16,
+        })
+    }
+
+    /// set gyro offsets from a vector, see `set_gyro_offsets`
+    pub fn set_gyro_offsets_vec(&mut self, offsets: Vector3d<i16>) -> Result<(), Mpu6050Error<E>> {
+        self.set_gyro_offsets(offsets.x, offsets.y, offsets.z)
+    }
+
+    /// get gyro fine tune offsets (raw counts), the residual from calibration that is added to every gyro reading
+    pub fn get_gyro_fine_tune_offsets(&self) -> Vector3d<i32> {
+        self.conv.gyro_fine_tune_offsets
+    }
+
+    /// set gyro fine tune offsets (raw counts), e.g. to restore a persisted calibration together with
+    /// `set_gyro_offsets`
+    pub fn set_gyro_fine_tune_offsets(&mut self, offsets: Vector3d<i32>) {
+        self.conv.gyro_fine_tune_offsets = offsets;
+    }
+
+    /// get accel fine tune offsets (raw counts), see `get_gyro_fine_tune_offsets`
+    pub fn get_accel_fine_tune_offsets(&self) -> Vector3d<i32> {
+        self.conv.acc_fine_tune_offsets
+    }
+
+    /// set accel fine tune offsets (raw counts), see `set_gyro_fine_tune_offsets`
+    pub fn set_accel_fine_tune_offsets(&mut self, offsets: Vector3d<i32>) {
+        self.conv.acc_fine_tune_offsets = offsets;
+    }
+
+    /// get accel offsets
+    /// The accel offset registers are in +-16g format (2048 LSB/g). Bit 0 is reserved and masked out.
+    pub fn get_accel_offsets(&mut self) -> Result<Vector3d<i32>, Mpu6050Error<E>> {
+        let mut buf: [u8; 2] = [0; 2];
+        let mut offsets: Vector3d<i32> = Vector3d::<i32>::default();
+
+        self.read_bytes(XA_OFFS_USRH, &mut buf)?;
+        offsets.x = read_word_2c(&buf[0..2]) & !(ACCEL_OFFS_RESERVED_MASK as i32);
+        self.read_bytes(YA_OFFS_USRH, &mut buf)?;
+        offsets.y = read_word_2c(&buf[0..2]) & !(ACCEL_OFFS_RESERVED_MASK as i32);
+        self.read_bytes(ZA_OFFS_USRH, &mut buf)?;
+        offsets.z = read_word_2c(&buf[0..2]) & !(ACCEL_OFFS_RESERVED_MASK as i32);
+
+        Ok(offsets)
+    }
+
+    /// set accel offsets
+    /// The accel offset registers are in +-16g format (2048 LSB/g). Bit 0 of each register is reserved,
+    /// so the current value is read first and bit 0 is preserved. Offsets therefore have a resolution of 2.
+    pub fn set_accel_offsets(
+        &mut self,
+        x_offset: i16,
+        y_offset: i16,
+        z_offset: i16,
+    ) -> Result<(), Mpu6050Error<E>> {
+        #[cfg(feature = "defmt")]
+        debug!(
+            "Setting accel offsets: x: {}, y: {}, z: {}",
+            x_offset, y_offset, z_offset
+        );
+        for (reg, offset) in [
+            (XA_OFFS_USRH, x_offset),
+            (YA_OFFS_USRH, y_offset),
+            (ZA_OFFS_USRH, z_offset),
+        ] {
+            self.write_word_masked(reg, offset as u16, ACCEL_OFFS_RESERVED_MASK)?;
+        }
+        Ok(())
+    }
+
+    /// Calibrate gyro and update offsets
+    /// To calibrate the gyro, the sensor must be stationary. The sensor should be placed on a flat, level surface. The gyro offset is the average of the readings.
+    /// Returns `CalibrationFailed` with the last mean, if the target isn't reached within the maximum number of steps
+    pub fn calibrate_gyro<D: DelayMs<u8>, F: FnMut(usize)>(
+        &mut self,
+        delay: &mut D,
+        callback: F,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let (converged, mean) =
+            self.calibrate_gyro_steps(delay, GyroCalibrationParams::default(), callback)?;
+        if !converged {
+            return Err(Mpu6050Error::CalibrationFailed(mean));
+        }
+        Ok(())
+    }
+
+    /// Same as `calibrate_gyro`, with custom calibration parameters.
+    /// Returns whether the target mean was reached within params.max_steps iterations
+    pub fn calibrate_gyro_with_params<D: DelayMs<u8>, F: FnMut(usize)>(
+        &mut self,
+        delay: &mut D,
+        params: GyroCalibrationParams,
+        callback: F,
+    ) -> Result<bool, Mpu6050Error<E>> {
+        Ok(self.calibrate_gyro_steps(delay, params, callback)?.0)
+    }
+
+    /// Gyro calibration loop, returns whether it converged and the last mean
+    fn calibrate_gyro_steps<D: DelayMs<u8>, F: FnMut(usize)>(
+        &mut self,
+        delay: &mut D,
+        params: GyroCalibrationParams,
+        mut callback: F,
+    ) -> Result<(bool, Vector3d<f32>), Mpu6050Error<E>> {
+        #[cfg(feature = "defmt")]
+        info!("Calibrating gyro");
+
+        // first set current offsets to 0, fine tune offsets as well
+        self.set_gyro_offsets(0, 0, 0)?;
+        self.conv.gyro_fine_tune_offsets = Vector3d::<i32>::default();
+
+        let mut offsets_found = false;
+        let mut calibration_step: usize = 0;
+        let mut mean = Vector3d::<f32>::default();
+        while !offsets_found && calibration_step < params.max_steps {
+            // get mean gyro readings
+            mean = self.calibrate_mean_sensor_with(
+                GYRO_REGX_H,
+                delay,
+                params.discard_count as i32,
+                params.sample_count as i32,
+                params.settle_delay_ms,
+            )?;
+
+            // calculate new offsets. This is repeated until the mean is close to 0 or we
+            // reach max_steps iterations
+            #[allow(unused_variables)]
+            let (updated_offsets, converged) =
+                self.calibrate_gyro_iteration(mean, params.target_mean)?;
+
+            #[cfg(feature = "defmt")]
+            info!(
+                "Calibration step: {}\n  Mean: x = {}, y  = {}, z = {}\n  Found Offsets: x = {}, y  = {}, z = {}",
+                calibration_step, mean.x, mean.y, mean.z, updated_offsets.x, updated_offsets.y, updated_offsets.z
+            );
+            // callback is any
+            callback(calibration_step);
+
+            offsets_found = converged;
+            calibration_step += 1;
+        }
+
+        Ok((offsets_found, mean))
+    }
+
+    /// One gyro calibration iteration for the mean of the last readings: updates the offset registers
+    /// and returns them, with whether the mean was within target. If so, the remaining mean (below what
+    /// the offset registers can resolve) is kept as fine tune offsets
+    fn calibrate_gyro_iteration(
+        &mut self,
+        mean: Vector3d<f32>,
+        target_mean: f32,
+    ) -> Result<(Vector3d<i32>, bool), Mpu6050Error<E>> {
+        let offsets = self.get_gyro_offsets()?;
+        let updated_offsets = gyro_calibration_offsets(offsets, mean, target_mean);
+        self.set_gyro_offsets(
+            updated_offsets.x as i16,
+            updated_offsets.y as i16,
+            updated_offsets.z as i16,
+        )?;
+
+        let converged = calibration_converged(mean, target_mean);
+        if converged {
+            self.conv.gyro_fine_tune_offsets = calibration_residual(mean);
+        }
+        Ok((updated_offsets, converged))
+    }
+
+    /// Calibrate accel and update offsets
+    /// To calibrate the accel, the sensor must be stationary and placed on a flat, level surface with
+    /// the z axis pointing up. The x and y readings are driven towards 0 g, the z reading towards +1 g.
+    /// The accel offset registers are in +-16g format with a reserved bit 0, so whatever the hardware
+    /// offsets can't resolve is kept as a fine tune offset and applied to every accel reading.
+    /// Returns `CalibrationFailed` with the last distance to the target (raw counts), if the target isn't
+    /// reached within the maximum number of steps, e.g. on a tilted board. No fine tune offsets are kept then
+    pub fn calibrate_accel<D: DelayMs<u8>, F: FnMut(usize)>(
+        &mut self,
+        delay: &mut D,
+        mut callback: F,
+    ) -> Result<(), Mpu6050Error<E>> {
+        // the target is for the 2g range, scale it to the current one
+        let target_mean = ACCEL_CALIBRATION_TARGET_MEAN * self.conv.acc_sensitivity / ACCEL_SENS.0;
+
+        #[cfg(feature = "defmt")]
+        info!("Calibrating accel");
+
+        // start from the current hardware offsets, as they hold the factory trim. Fine tune offsets are reset.
+        self.conv.acc_fine_tune_offsets = Vector3d::<i32>::default();
+
+        let mut offsets_found = false;
+        let mut calibration_step: usize = 0;
+        let mut error = Vector3d::<f32>::default();
+        while !offsets_found && calibration_step < CALIBRATION_MAX_STEPS {
+            // get mean accel readings and the distance to (0, 0, 1) g
+            let mean = self.calibrate_mean_sensor(ACC_REGX_H, delay)?;
+            error = Vector3d::<f32> {
+                x: mean.x,
+                y: mean.y,
+                z: mean.z - self.conv.acc_sensitivity,
+            };
+
+            // calculate new offsets. This is repeated until the error is close to 0 or we
+            // reach CALIBRATION_MAX_STEPS iterations
+            #[allow(unused_variables)]
+            let (updated_offsets, converged) =
+                self.calibrate_accel_iteration(error, target_mean)?;
+
+            #[cfg(feature = "defmt")]
+            info!(
+                "Calibration step: {}\n  Error: x = {}, y  = {}, z = {}\n  Found Offsets: x = {}, y  = {}, z = {}",
+                calibration_step, error.x, error.y, error.z, updated_offsets.x, updated_offsets.y, updated_offsets.z
+            );
+            callback(calibration_step);
+
+            offsets_found = converged;
+            calibration_step += 1;
+        }
+
+        if !offsets_found {
+            return Err(Mpu6050Error::CalibrationFailed(error));
+        }
+        Ok(())
+    }
+
+    /// One accel calibration iteration for the error of the last readings, see `calibrate_gyro_iteration`.
+    /// Once converged, no offset register changes, so the error still holds and is kept as fine tune offsets
+    fn calibrate_accel_iteration(
+        &mut self,
+        error: Vector3d<f32>,
+        target_mean: f32,
+    ) -> Result<(Vector3d<i32>, bool), Mpu6050Error<E>> {
+        let offsets = self.get_accel_offsets()?;
+        // offset register counts per measurement count, 1/8 for the 2g range
+        let offset_scale = ACCEL_OFFS_SENS / self.conv.acc_sensitivity;
+        let updated_offsets = accel_calibration_offsets(offsets, error, target_mean, offset_scale);
+        self.set_accel_offsets(
+            updated_offsets.x as i16,
+            updated_offsets.y as i16,
+            updated_offsets.z as i16,
+        )?;
+
+        let converged = calibration_converged(error, target_mean);
+        if converged {
+            self.conv.acc_fine_tune_offsets = calibration_residual(error);
+        }
+        Ok((updated_offsets, converged))
+    }
+
+    fn calibrate_mean_sensor<D: DelayMs<u8>>(
+        &mut self,
+        reg: u8,
+        delay: &mut D,
+    ) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        self.calibrate_mean_sensor_with(
+            reg,
+            delay,
+            CALIBRATION_DISCARD_COUNT,
+            CALIBRATION_MEASUREMENT_COUNT,
+            CALIBRATION_SAMPLE_DELAY_MS,
+        )
+    }
+
+    fn calibrate_mean_sensor_with<D: DelayMs<u8>>(
+        &mut self,
+        reg: u8,
+        delay: &mut D,
+        discard_count: i32,
+        count: i32,
+        delay_ms: u8,
+    ) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
+        let mut sum: Vector3d<i32> = Vector3d::<i32>::default();
+        let count = count.max(1);
+
+        // discard first readings
+        for _ in 0..discard_count {
+            let _ = self.read_rot_i32(reg)?;
+            delay.delay_ms(delay_ms);
+        }
+        for _ in 0..count {
+            let rot = self.read_rot_i32(reg)?;
+
+            sum += rot;
+            delay.delay_ms(delay_ms);
+        }
+        Ok(calibration_mean(sum, count))
+    }
+
+    /// Writes word to register, without delay (see `write_byte`)
+    pub fn write_word(&mut self, reg: u8, word_value: u16) -> Result<(), Mpu6050Error<E>> {
+        let data = [reg, (word_value >> 8) as u8, (word_value & 0x00FF) as u8];
+        self.i2c.write(self.slave_addr, &data)
+           .map_err(Mpu6050Error::I2c)?;
+        Ok(())
+    }
+
+    /// Writes word to register, keeping the bits in preserve_mask as they are (read-modify-write),
+    /// e.g. reserved bits sharing a register pair with a value
+    pub fn write_word_masked(
+        &mut self,
+        reg: u8,
+        word_value: u16,
+        preserve_mask: u16,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let mut buf: [u8; 2] = [0; 2];
+        self.read_bytes(reg, &mut buf)?;
+        let preserved = u16::from_be_bytes(buf) & preserve_mask;
+        self.write_word(reg, (word_value & !preserve_mask) | preserved)
+    }
+
+    /// Writes byte to register. Returns right after the write, configuration registers can be accessed
+    /// again immediately. Use `write_byte_with_delay` for writes that need time to take effect (reset, clock source, wake)
+    pub fn write_byte(&mut self, reg: u8, byte: u8) -> Result<(), Mpu6050Error<E>> {
+        self.i2c.write(self.slave_addr, &[reg, byte])
+           .map_err(Mpu6050Error::I2c)?;
+        Ok(())
+    }
+
+    /// Writes data to consecutive registers starting at start_reg, in a single transaction (the register
+    /// address auto increments). At most `MAX_BLOCK_WRITE` bytes, `WriteTooLong` otherwise
+    pub fn write_bytes(&mut self, start_reg: u8, data: &[u8]) -> Result<(), Mpu6050Error<E>> {
+        if data.len() > MAX_BLOCK_WRITE {
+            return Err(Mpu6050Error::WriteTooLong(data.len()));
+        }
+        let mut buf: [u8; MAX_BLOCK_WRITE + 1] = [0; MAX_BLOCK_WRITE + 1];
+        buf[0] = start_reg;
+        buf[1..=data.len()].copy_from_slice(data);
+        self.i2c
+            .write(self.slave_addr, &buf[..=data.len()])
+            .map_err(Mpu6050Error::I2c)?;
+        Ok(())
+    }
+
+    /// Writes byte to register and reads it back, returns `WriteVerifyFailed` if the value differs, e.g. for a
+    /// write dropped on a noisy bus. Meant for configuration registers, not for registers with self clearing
+    /// or read only bits (e.g. the reset bits of USER_CTRL, PWR_MGMT_1), which never read back as written
+    pub fn write_byte_verified(&mut self, reg: u8, byte: u8) -> Result<(), Mpu6050Error<E>> {
+        self.write_byte(reg, byte)?;
+        let got = self.read_byte(reg)?;
+        if got != byte {
+            return Err(Mpu6050Error::WriteVerifyFailed {
+                reg,
+                expected: byte,
+                got,
+            });
+        }
+        Ok(())
+    }
+
+    /// Writes byte to register and waits settle_ms before returning
+    pub fn write_byte_with_delay<D: DelayMs<u8>>(
+        &mut self,
+        reg: u8,
+        byte: u8,
+        delay: &mut D,
+        settle_ms: u8,
+    ) -> Result<(), Mpu6050Error<E>> {
+        self.write_byte(reg, byte)?;
+        delay.delay_ms(settle_ms);
+        Ok(())
+    }
+
+    /// Enables bit n at register address reg
+    pub fn write_bit(&mut self, reg: u8, bit_n: u8, enable: bool) -> Result<(), Mpu6050Error<E>> {
+        let mut byte: [u8; 1] = [0; 1];
+        self.read_bytes(reg, &mut byte)?;
+        bits::set_bit(&mut byte[0], bit_n, enable);
+        self.write_byte(reg, byte[0])
+    }
+
+    /// Write bits data at reg from start_bit to start_bit+length
+    pub fn write_bits(
+        &mut self,
+        reg: u8,
+        start_bit: u8,
+        length: u8,
+        data: u8,
+    ) -> Result<(), Mpu6050Error<E>> {
+        let mut byte: [u8; 1] = [0; 1];
+        self.read_bytes(reg, &mut byte)?;
+        bits::set_bits(&mut byte[0], start_bit, length, data);
+        self.write_byte(reg, byte[0])
+    }
+
+    /// Read bit n from register
+    fn read_bit(&mut self, reg: u8, bit_n: u8) -> Result<u8, Mpu6050Error<E>> {
+        let mut byte: [u8; 1] = [0; 1];
+        self.read_bytes(reg, &mut byte)?;
+        Ok(bits::get_bit(byte[0], bit_n))
+    }
+
+    /// Read bits at register reg, starting with bit start_bit, until start_bit+length
+    pub fn read_bits(&mut self, reg: u8, start_bit: u8, length: u8) -> Result<u8, Mpu6050Error<E>> {
+        let mut byte: [u8; 1] = [0; 1];
+        self.read_bytes(reg, &mut byte)?;
+        Ok(bits::get_bits(byte[0], start_bit, length))
+    }
+
+    /// Reads byte from register
+    pub fn read_byte(&mut self, reg: u8) -> Result<u8, Mpu6050Error<E>> {
+        let mut byte: [u8; 1] = [0; 1];
+        self.i2c.write_read(self.slave_addr, &[reg], &mut byte)
+            .map_err(Mpu6050Error::I2c)?;
+        Ok(byte[0])
+    }
+
+    /// Reads series of bytes into buf from specified reg. Reads exactly buf.len() bytes, from consecutive
+    /// registers (auto increment) except for FIFO_R_W. An empty buf is a bug in the caller
+    pub fn read_bytes(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Mpu6050Error<E>> {
+        debug_assert!(!buf.is_empty(), "read_bytes with an empty buffer");
+        self.i2c.write_read(self.slave_addr, &[reg], buf)
+            .map_err(Mpu6050Error::I2c)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate std;
+    use std::vec;
+
+    use embedded_hal_mock::delay::MockNoop;
+    use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    #[test]
+    fn read_word_2c_test() {
+        assert_eq!(read_word_2c(&[0x80, 0x00]), -32768);
+        assert_eq!(read_word_2c(&[0x7F, 0xFF]), 32767);
+        assert_eq!(read_word_2c(&[0xFF, 0xFF]), -1);
+        assert_eq!(read_word_2c(&[0x00, 0x01]), 1);
+        assert_eq!(read_word_2c(&[0x00, 0x00]), 0);
+    }
+
+    #[test]
+    fn gyro_calibration_offsets_clamp_test() {
+        let target = GYRO_CALIBRATION_TARGET_MEAN;
+        // regular step: mean / 4
+        let offsets = gyro_calibration_offsets(
+            Vector3d::<i32> { x: 100, y: 0, z: 0 },
+            Vector3d::<f32> {
+                x: 40.0,
+                y: 0.0,
+                z: -1.0,
+            },
+            target,
+        );
+        assert_eq!((offsets.x, offsets.y, offsets.z), (90, 0, 0));
+
+        // extreme bias near the register limits is clamped instead of wrapped
+        let start = Vector3d::<i32> {
+            x: i16::MAX as i32 - 10,
+            y: i16::MIN as i32 + 10,
+            z: 0,
+        };
+        let mean = Vector3d::<f32> {
+            x: -40000.0,
+            y: 40000.0,
+            z: 0.0,
+        };
+        let offsets = gyro_calibration_offsets(start, mean, target);
+        assert_eq!((offsets.x, offsets.y), (i16::MAX as i32, i16::MIN as i32));
+        assert_eq!(offsets.x as i16, i16::MAX);
+    }
+
+    #[test]
+    fn write_read_bits_test() {
+        let expectations = [
+            // CLKSEL (bits 2:0) = 0b011, other bits kept
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0b0100_1000]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0b0100_1011]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0b0100_1011]),
+            // FS_SEL (bits 4:3)
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0b1111_1111]),
+            I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0b1110_0111]),
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0b1110_1111]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.write_bits(
+            PWR_MGMT_1::ADDR,
+            PWR_MGMT_1::CLKSEL.bit,
+            PWR_MGMT_1::CLKSEL.length,
+            0b011,
+        )
+        .unwrap();
+        assert_eq!(
+            mpu.read_bits(
+                PWR_MGMT_1::ADDR,
+                PWR_MGMT_1::CLKSEL.bit,
+                PWR_MGMT_1::CLKSEL.length
+            )
+            .unwrap(),
+            0b011
+        );
+        mpu.write_bits(
+            GYRO_CONFIG::ADDR,
+            GYRO_CONFIG::FS_SEL.bit,
+            GYRO_CONFIG::FS_SEL.length,
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            mpu.read_bits(
+                GYRO_CONFIG::ADDR,
+                GYRO_CONFIG::FS_SEL.bit,
+                GYRO_CONFIG::FS_SEL.length
+            )
+            .unwrap(),
+            1
+        );
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn set_range_sensitivity_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0x08]),
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x18]),
+            // 8192 counts at 16g
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0x20, 0x00, 0, 0, 0, 0]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.set_gyro_range(GyroRange::D500).unwrap();
+        assert_eq!(mpu.gyro_sensitivity(), GYRO_SENS.1);
+        mpu.set_accel_range(AccelRange::G16).unwrap();
+        assert_eq!(mpu.acc_sensitivity(), ACCEL_SENS.3);
+        let acc = mpu.get_acc().unwrap();
+        assert_eq!(acc.x, 4.0);
+        assert_eq!(acc.as_ms2().x, 4.0 * STANDARD_GRAVITY);
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn range_u8_round_trip_test() {
+        for range in [
+            AccelRange::G2,
+            AccelRange::G4,
+            AccelRange::G8,
+            AccelRange::G16,
+        ] {
+            let byte: u8 = range.into();
+            assert_eq!(AccelRange::from(byte), range);
+        }
+        for range in [
+            GyroRange::D250,
+            GyroRange::D500,
+            GyroRange::D1000,
+            GyroRange::D2000,
+        ] {
+            let byte: u8 = range.into();
+            assert_eq!(GyroRange::from(byte), range);
+        }
+        assert_eq!(u8::from(GyroRange::D2000), 3);
+    }
+
+    #[test]
+    fn verify_non_default_addr_test() {
+        // A0 pulled up: bus address 0x69, WHOAMI still reads 0x68
+        let expectations = [I2cTransaction::write_read(0x69, vec![WHOAMI], vec![0x68])];
+        let mut mpu = Mpu6050::new_with_addr(I2cMock::new(&expectations), 0x69);
+        assert!(mpu.verify().is_ok());
+        assert_eq!(mpu.get_device_model(), Some(DeviceModel::Mpu6050));
+        mpu.i2c.done();
+
+        let expectations = [I2cTransaction::write_read(0x69, vec![WHOAMI], vec![0x69])];
+        let mut mpu = Mpu6050::new_with_addr(I2cMock::new(&expectations), 0x69);
+        assert!(matches!(
+            mpu.verify(),
+            Err(Mpu6050Error::InvalidChipId(0x69))
+        ));
+        assert_eq!(mpu.get_device_model(), None);
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn init_preserves_constructor_ranges_test() {
+        let expectations = [
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]),
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68]),
+            // accel HPF (5Hz, bits 2:0) left as configured for motion detection
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x01]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x11]),
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0x10]),
+        ];
+        let mut mpu = Mpu6050::new_with_sens(
+            I2cMock::new(&expectations),
+            AccelRange::G8,
+            GyroRange::D1000,
+        );
+        mpu.init(&mut MockNoop::new()).unwrap();
+        assert_eq!(mpu.conv.acc_sensitivity, ACCEL_SENS.2);
+        assert_eq!(mpu.conv.gyro_sensitivity, GYRO_SENS.2);
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn init_wake_options_test() {
+        let verify = I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68]);
+        let ranges = [
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x00]),
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0x00]),
+        ];
+
+        // already awake: no PWR_MGMT_1 write
+        let mut expectations = vec![verify.clone()];
+        expectations.extend_from_slice(&ranges);
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let config = Mpu6050Config {
+            skip_wake: true,
+            ..Default::default()
+        };
+        mpu.init_with_config(&mut MockNoop::new(), config).unwrap();
+        mpu.i2c.done();
+
+        let mut expectations = vec![
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]),
+            verify,
+        ];
+        expectations.extend_from_slice(&ranges);
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        // longer than a single DelayMs<u8> call
+        let config = Mpu6050Config {
+            wake_delay_ms: Some(1000),
+            ..Default::default()
+        };
+        let mut delay = DelayTotal(0);
+        mpu.init_with_config(&mut delay, config).unwrap();
+        assert_eq!(delay.0, 1000);
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn init_single_sensor_test() {
+        let wake = [
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]),
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68]),
+        ];
+        let ranges = [
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x00]),
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0x00]),
+        ];
+
+        let mut expectations = wake.to_vec();
+        expectations.extend_from_slice(&ranges);
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![PWR_MGMT_2::ADDR],
+            vec![0x00],
+        ));
+        expectations.push(I2cTransaction::write(
+            0x68,
+            vec![PWR_MGMT_2::ADDR, 0b0011_1000],
+        ));
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.init_gyro_only(&mut MockNoop::new()).unwrap();
+        mpu.i2c.done();
+
+        let mut expectations = wake.to_vec();
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![PWR_MGMT_1::ADDR],
+            vec![0x01],
+        ));
+        expectations.push(I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x00]));
+        expectations.extend_from_slice(&ranges);
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![PWR_MGMT_2::ADDR],
+            vec![0x00],
+        ));
+        expectations.push(I2cTransaction::write(
+            0x68,
+            vec![PWR_MGMT_2::ADDR, 0b0000_0111],
+        ));
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.init_accel_only(&mut MockNoop::new()).unwrap();
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn temp_units_test() {
+        // 0xFC90 = -880 -> -880 / 340 + 36.53 = 33.94°C
+        let read = I2cTransaction::write_read(0x68, vec![TEMP_OUT_H], vec![0xFC, 0x90]);
+        let expectations = [read.clone(), read.clone(), read.clone(), read];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert_eq!(mpu.get_temp_raw().unwrap(), -880);
+        let celsius = mpu.get_temp().unwrap();
+        assert!((celsius - 33.9418).abs() < 1e-3);
+        assert!((mpu.get_temp_fahrenheit().unwrap() - (celsius * 1.8 + 32.0)).abs() < 1e-3);
+        assert!((mpu.get_temp_kelvin().unwrap() - (celsius + 273.15)).abs() < 1e-3);
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn release_test() {
+        let expectations = [I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68])];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert_eq!(mpu.who_am_i().unwrap(), 0x68);
+        let mut i2c = mpu.release();
+        i2c.done();
+    }
+
+    #[test]
+    fn get_measurement_test() {
+        // acc (0, 0, 1g), gyro (131, 0, 0) = 1°/s, temp raw 0
+        let expectations = [I2cTransaction::write_read(
+            0x68,
+            vec![ACC_REGX_H],
+            vec![0, 0, 0, 0, 0x40, 0, 0, 0, 0, 0x83, 0, 0, 0, 0],
+        )];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let m = mpu.get_measurement().unwrap();
+        assert_eq!(m.acc, [0.0, 0.0, 1.0]);
+        assert!((m.gyro[0] - PI_180).abs() < 1e-6);
+        assert_eq!(m.temp, TEMP_OFFSET);
+        mpu.i2c.done();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn measurement_serde_test() {
+        let m = Measurement {
+            acc: [0.0, 0.5, 1.0],
+            gyro: [0.25, -0.125, 0.0],
+            temp: 24.5,
+        };
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(serde_json::from_str::<Measurement>(&json).unwrap(), m);
+    }
+
+    #[test]
+    fn measurement_fixed_test() {
+        let expectations = [I2cTransaction::write_read(
+            0x68,
+            vec![ACC_REGX_H],
+            vec![
+                0x10, 0x00, 0x00, 0x00, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x41, 0x00, 0x00,
+            ],
+        )];
+        let mut mpu =
+            Mpu6050::new_with_sens(I2cMock::new(&expectations), AccelRange::G4, GyroRange::D500);
+        let fixed = mpu.get_measurement_fixed().unwrap();
+        assert_eq!(core::mem::size_of::<MeasurementFixed>(), 16);
+        assert_eq!(fixed.accel_range, 1);
+        assert_eq!(fixed.gyro_range, 1);
+        let m = fixed.to_measurement();
+        assert_eq!(m.acc, [0.5, 0.0, -0.5]);
+        assert!((m.gyro[1] - 65.0 / 65.5 * PI_180).abs() < 1e-6);
+        assert!((m.temp - 36.53).abs() < 1e-3);
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn slave_read_test() {
+        // slot 1 reads 6 bytes from a magnetometer at 0x1E, register 0x03. slot 0 is enabled with 2 bytes
+        let expectations = [
+            I2cTransaction::write(0x68, vec![0x28, 0x9E]),
+            I2cTransaction::write(0x68, vec![0x29, 0x03]),
+            I2cTransaction::write(0x68, vec![0x2A, 0x86]),
+            I2cTransaction::write_read(0x68, vec![0x27], vec![0x82]),
+            I2cTransaction::write_read(0x68, vec![0x2A], vec![0x86]),
+            I2cTransaction::write_read(0x68, vec![0x4B], vec![1, 2, 3, 4, 5, 6]),
+            // too short for the 6 bytes configured
+            I2cTransaction::write_read(0x68, vec![0x27], vec![0x82]),
+            I2cTransaction::write_read(0x68, vec![0x2A], vec![0x86]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.configure_slave(1, 0x1E, 0x03, 6).unwrap();
+        let mut buf = [0u8; 6];
+        mpu.read_slave_data(1, &mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 3, 4, 5, 6]);
+        assert!(matches!(
+            mpu.read_slave_data(1, &mut buf[..3]),
+            Err(Mpu6050Error::BufferTooSmall(6))
+        ));
+        assert!(matches!(
+            mpu.configure_slave(4, 0x1E, 0x03, 6),
+            Err(Mpu6050Error::InvalidSlaveSlot(4))
+        ));
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn calibrate_gyro_not_converged_test() {
+        let params = GyroCalibrationParams {
+            max_steps: 1,
+            target_mean: 1.5,
+            sample_count: 1,
+            discard_count: 0,
+            settle_delay_ms: 0,
+        };
+        let expectations = [
+            I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0, 0]),
+            // x axis is 10 counts off
+            I2cTransaction::write_read(0x68, vec![GYRO_REGX_H], vec![0, 10, 0, 0, 0, 0]),
+            I2cTransaction::write_read(0x68, vec![XG_OFFS_USRH], vec![0, 0]),
+            I2cTransaction::write_read(0x68, vec![YG_OFFS_USRH], vec![0, 0]),
+            I2cTransaction::write_read(0x68, vec![ZG_OFFS_USRH], vec![0, 0]),
+            I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0xFF, 0xFE]),
+            I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0, 0]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let converged = mpu
+            .calibrate_gyro_with_params(&mut MockNoop::new(), params, |_| {})
+            .unwrap();
+        assert!(!converged);
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn gyro_calibrator_test() {
+        let params = GyroCalibrationParams {
+            max_steps: 2,
+            target_mean: 1.5,
+            sample_count: 4,
+            discard_count: 1,
+            settle_delay_ms: 0,
+        };
+        let mut expectations = vec![
+            I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0, 0]),
+        ];
+        // first iteration: x axis 10 counts off, second: within target
+        for (x, offset) in [(10, [0x00, 0x00]), (1, [0xFF, 0xFE])] {
+            for _ in 0..5 {
+                expectations.push(I2cTransaction::write_read(
+                    0x68,
+                    vec![GYRO_REGX_H],
+                    vec![0, x, 0, 0, 0, 0],
+                ));
+            }
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![XG_OFFS_USRH],
+                offset.to_vec(),
+            ));
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![YG_OFFS_USRH],
+                vec![0, 0],
+            ));
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![ZG_OFFS_USRH],
+                vec![0, 0],
+            ));
+            expectations.push(I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0xFF, 0xFE]));
+            expectations.push(I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0, 0]));
+            expectations.push(I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0, 0]));
+        }
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut calibrator = GyroCalibrator::new::<()>(params, 2).unwrap();
+
+        // 5 readings per iteration, at most 2 per step
+        let mut steps = 0;
+        let offsets = loop {
+            steps += 1;
+            match calibrator.step(&mut mpu).unwrap() {
+                CalibrationProgress::InProgress(fraction) => {
+                    assert!((0.0..1.0).contains(&fraction))
+                }
+                CalibrationProgress::Done(offsets) => break offsets,
+            }
+        };
+        assert_eq!(steps, 6);
+        assert_eq!((offsets.x, offsets.y, offsets.z), (-2, 0, 0));
+        assert_eq!(mpu.conv.gyro_fine_tune_offsets.x, -1);
+        // done is sticky, no more bus traffic
+        assert_eq!(
+            calibrator.step(&mut mpu).unwrap(),
+            CalibrationProgress::Done(offsets)
+        );
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn gyro_calibrator_failed_test() {
+        let mut params = GyroCalibrationParams {
+            max_steps: 0,
+            target_mean: 1.5,
+            sample_count: 2,
+            discard_count: 0,
+            settle_delay_ms: 0,
+        };
+        assert!(matches!(
+            GyroCalibrator::new::<()>(params, 2),
+            Err(Mpu6050Error::InvalidCalibrationParams)
+        ));
+
+        params.max_steps = 1;
+        let mut expectations = vec![
+            I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0, 0]),
+        ];
+        // x axis 10 counts off
+        for _ in 0..2 {
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![GYRO_REGX_H],
+                vec![0, 10, 0, 0, 0, 0],
+            ));
+        }
+        for reg in [XG_OFFS_USRH, YG_OFFS_USRH, ZG_OFFS_USRH] {
+            expectations.push(I2cTransaction::write_read(0x68, vec![reg], vec![0, 0]));
+        }
+        expectations.push(I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0xFF, 0xFE]));
+        expectations.push(I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0, 0]));
+        expectations.push(I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0, 0]));
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut calibrator = GyroCalibrator::new::<()>(params, 2).unwrap();
+
+        let mean = Vector3d::<f32> {
+            x: 10.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        assert!(matches!(
+            calibrator.step(&mut mpu),
+            Err(Mpu6050Error::CalibrationFailed(m)) if m == mean
+        ));
+        // failed is sticky, no more bus traffic
+        assert!(matches!(
+            calibrator.step(&mut mpu),
+            Err(Mpu6050Error::CalibrationFailed(m)) if m == mean
+        ));
+        mpu.i2c.done();
+    }
+
+    #[test]
+    fn calibrate_gyro_failed_test() {
+        // x axis always 10 counts off, offsets never take effect
+        let mut expectations = vec![
+            I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0, 0]),
+            I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0, 0]),
+        ];
+        for _ in 0..CALIBRATION_MAX_STEPS {
+            for _ in 0..(CALIBRATION_DISCARD_COUNT + CALIBRATION_MEASUREMENT_COUNT) {
+                expectations.push(I2cTransaction::write_read(
+                    0x68,
+                    vec![GYRO_REGX_H],
+                    vec![0, 10, 0, 0, 0, 0],
+                ));
+            }
+            for reg in [XG_OFFS_USRH, YG_OFFS_USRH, ZG_OFFS_USRH] {
+                expectations.push(I2cTransaction::write_read(0x68, vec![reg], vec![0, 0]));
+            }
+            expectations.push(I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0xFF, 0xFE]));
+            expectations.push(I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0, 0]));
+            expectations.push(I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0, 0]));
+        }
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        match mpu.calibrate_gyro(&mut MockNoop::new(), |_| {}) {
+            Err(Mpu6050Error::CalibrationFailed(mean)) => assert_eq!(mean.x, 10.0),
+            _ => panic!("expected CalibrationFailed"),
+        }
+        mpu.i2c.done();
+    }
+
+    /// Expectations for one accel calibration step: readings with x off by x_counts and z at 1g, then the
+    /// offset update from the x offset read back to the x offset written
+    fn accel_calibration_step(
+        expectations: &mut std::vec::Vec<I2cTransaction>,
+        x_counts: u8,
+        x_offset: [u8; 2],
+        new_x_offset: [u8; 2],
+    ) {
+        for _ in 0..(CALIBRATION_DISCARD_COUNT + CALIBRATION_MEASUREMENT_COUNT) {
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![0, x_counts, 0, 0, 0x40, 0],
+            ));
+        }
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![XA_OFFS_USRH],
+            x_offset.to_vec(),
+        ));
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![YA_OFFS_USRH],
+            vec![0, 0],
+        ));
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![ZA_OFFS_USRH],
+            vec![0, 0],
+        ));
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![XA_OFFS_USRH],
+            x_offset.to_vec(),
+        ));
+        expectations.push(I2cTransaction::write(
+            0x68,
+            vec![XA_OFFS_USRH, new_x_offset[0], new_x_offset[1]],
+        ));
+        for reg in [YA_OFFS_USRH, ZA_OFFS_USRH].iter().copied() {
+            expectations.push(I2cTransaction::write_read(0x68, vec![reg], vec![0, 0]));
+            expectations.push(I2cTransaction::write(0x68, vec![reg, 0, 0]));
+        }
     }
 
-    /// Init wakes MPU6050 and verifies register addr, e.g. in i2c
-    pub fn init<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
-        self.wake(delay)?;
-        self.verify()?;
-        self.set_accel_range(AccelRange::G2)?;
-        self.set_gyro_range(GyroRange::D250)?;
-        self.set_accel_hpf(ACCEL_HPF::_RESET)?;
-        Ok(())
+    #[test]
+    fn calibrate_accel_test() {
+        // x axis 40 counts off: offset moves by 40/8 = 5, bit 0 reserved -> -6. Then 3 counts off, within target
+        let mut expectations = vec![];
+        accel_calibration_step(&mut expectations, 40, [0, 0], [0xFF, 0xFA]);
+        accel_calibration_step(&mut expectations, 3, [0xFF, 0xFA], [0xFF, 0xFA]);
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut steps = 0;
+        mpu.calibrate_accel(&mut MockNoop::new(), |_| steps += 1)
+            .unwrap();
+        assert_eq!(steps, 2);
+        // the residual of the last, unchanged offsets is kept
+        assert_eq!(
+            mpu.conv.acc_fine_tune_offsets,
+            Vector3d { x: -3, y: 0, z: 0 }
+        );
+        mpu.i2c.done();
     }
 
-    /// Verifies device to address 0x68 with WHOAMI.addr() Register
-    fn verify(&mut self) -> Result<(), Mpu6050Error<E>> {
-        let address = self.read_byte(WHOAMI)?;
-        if address != DEFAULT_SLAVE_ADDR {
-            return Err(Mpu6050Error::InvalidChipId(address));
+    #[test]
+    fn calibrate_accel_failed_test() {
+        // tilted board: x axis always 40 counts off, offsets never take effect
+        let mut expectations = vec![];
+        for _ in 0..CALIBRATION_MAX_STEPS {
+            accel_calibration_step(&mut expectations, 40, [0, 0], [0xFF, 0xFA]);
         }
-        Ok(())
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        match mpu.calibrate_accel(&mut MockNoop::new(), |_| {}) {
+            Err(Mpu6050Error::CalibrationFailed(error)) => {
+                assert_eq!((error.x, error.z), (40.0, 0.0))
+            }
+            _ => panic!("expected CalibrationFailed"),
+        }
+        // no bias is baked into accel reads
+        assert_eq!(mpu.conv.acc_fine_tune_offsets, Vector3d::<i32>::default());
+        mpu.i2c.done();
     }
 
-    /// setup motion detection
-    /// sources:
-    /// * https://github.com/kriswiner/MPU6050/blob/a7e0c8ba61a56c5326b2bcd64bc81ab72ee4616b/MPU6050IMU.ino#L486
-    /// * https://arduino.stackexchange.com/a/48430
-    pub fn setup_motion_detection(&mut self) -> Result<(), Mpu6050Error<E>> {
-        self.write_byte(0x6B, 0x00)?;
-        // optional? self.write_byte(0x68, 0x07)?; // Reset all internal signal paths in the MPU-6050 by writing 0x07 to register 0x68;
-        self.write_byte(INT_PIN_CFG::ADDR, 0x20)?; //write register 0x37 to select how to use the interrupt pin. For an active high, push-pull signal that stays until register (decimal) 58 is read, write 0x20.
-        self.write_byte(ACCEL_CONFIG::ADDR, 0x01)?; //Write register 28 (==0x1C) to set the Digital High Pass Filter, bits 3:0. For example set it to 0x01 for 5Hz. (These 3 bits are grey in the data sheet, but they are used! Leaving them 0 means the filter always outputs 0.)
-        self.write_byte(MOT_THR, 10)?; //Write the desired Motion threshold to register 0x1F (For example, write decimal 20).
-        self.write_byte(MOT_DUR, 40)?; //Set motion detect duration to 1  ms; LSB is 1 ms @ 1 kHz rate
-        self.write_byte(0x69, 0x15)?; //to register 0x69, write the motion detection decrement and a few other settings (for example write 0x15 to set both free-fall and motion decrements to 1 and accelerometer start-up delay to 5ms total by adding 1ms. )
-        self.write_byte(INT_ENABLE::ADDR, 0x40)?; //write register 0x38, bit 6 (0x40), to enable motion detection interrupt.
-        Ok(())
+    #[test]
+    fn self_test_trim_test() {
+        assert_eq!(gyro_factory_trim(0), 0.0);
+        assert!((gyro_factory_trim(1) - 3275.0).abs() < 1e-3);
+        assert!((gyro_factory_trim(11) - 3275.0 * 1.046_f32.powi(10)).abs() < 1.0);
+        assert_eq!(accel_factory_trim(0), 0.0);
+        assert!((accel_factory_trim(1) - 1392.64).abs() < 1e-2);
+        assert!((accel_factory_trim(31) - 4096.0 * 0.92).abs() < 1.0);
+        assert!((self_test_deviation(1100.0, 1000.0) - 10.0).abs() < 1e-3);
     }
 
-    /// get whether or not motion has been detected (INT_STATUS, MOT_INT)
-    pub fn get_motion_detected(&mut self) -> Result<bool, Mpu6050Error<E>> {
-        Ok(self.read_bit(INT_STATUS::ADDR, INT_STATUS::MOT_INT)? != 0)
+    #[test]
+    fn fifo_overflow_reset_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0x10]),
+            // reset with FIFO enabled
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x40]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x40]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x00]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x04]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x40]),
+            I2cTransaction::write_read(0x68, vec![FIFO_COUNT_H], vec![0, 0]),
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0x00]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert!(mpu.fifo_overflow().unwrap());
+        mpu.reset_fifo().unwrap();
+        assert_eq!(mpu.fifo_count().unwrap(), 0);
+        assert!(!mpu.fifo_overflow().unwrap());
+        mpu.i2c.done();
     }
 
-    /// set accel high pass filter mode
-    pub fn set_accel_hpf(&mut self, mode: ACCEL_HPF) -> Result<(), Mpu6050Error<E>> {
-        Ok(self.write_bits(
-            ACCEL_CONFIG::ADDR,
-            ACCEL_CONFIG::ACCEL_HPF.bit,
-            ACCEL_CONFIG::ACCEL_HPF.length,
-            mode as u8,
-        )?)
+    #[test]
+    fn dump_registers_test() {
+        let mut regs = vec![0u8; REGISTER_DUMP_LEN];
+        regs[(PWR_MGMT_1::ADDR - REGISTER_DUMP_START) as usize] = 0x40;
+        regs[(PWR_MGMT_2::ADDR - REGISTER_DUMP_START) as usize] = 0x07;
+        let expectations = [I2cTransaction::write_read(0x68, vec![SMPLRT_DIV], regs)];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let dump = mpu.dump_registers().unwrap();
+        assert_eq!(dump.reg(PWR_MGMT_1::ADDR), Some(0x40));
+        assert_eq!(dump.reg(PWR_MGMT_2::ADDR), Some(0x07));
+        assert_eq!(dump.reg(SMPLRT_DIV), Some(0));
+        assert_eq!(dump.reg(WHOAMI), None);
+        assert_eq!(dump.reg(0x00), None);
+        mpu.i2c.done();
     }
 
-    /// get accel high pass filter mode
-    pub fn get_accel_hpf(&mut self) -> Result<ACCEL_HPF, Mpu6050Error<E>> {
-        let mode: u8 = self.read_bits(
-            ACCEL_CONFIG::ADDR,
-            ACCEL_CONFIG::ACCEL_HPF.bit,
-            ACCEL_CONFIG::ACCEL_HPF.length,
-        )?;
+    #[test]
+    fn motion_detect_status_test() {
+        let expectations = [I2cTransaction::write_read(
+            0x68,
+            vec![MOT_DETECT_STATUS::ADDR],
+            vec![0x40],
+        )];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let status = mpu.get_motion_detect_status().unwrap();
+        assert_eq!(
+            status,
+            MotionDetectStatus {
+                x_pos: true,
+                ..Default::default()
+            }
+        );
+        mpu.i2c.done();
+    }
 
-        Ok(ACCEL_HPF::from(mode))
+    #[test]
+    fn fsync_keeps_dlpf_test() {
+        let expectations = [
+            // DLPF_CFG = 3 is kept
+            I2cTransaction::write_read(0x68, vec![CONFIG::ADDR], vec![0x03]),
+            I2cTransaction::write(0x68, vec![CONFIG::ADDR, 0x2B]),
+            I2cTransaction::write_read(0x68, vec![CONFIG::ADDR], vec![0x2B]),
+            I2cTransaction::write_read(0x68, vec![CONFIG::ADDR], vec![0x2B]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.set_fsync(ExtSync::ACCEL_XOUT_L).unwrap();
+        assert_eq!(mpu.get_fsync().unwrap(), ExtSync::ACCEL_XOUT_L);
+        assert_eq!(mpu.get_dlpf().unwrap(), DLPF::_44);
+        mpu.i2c.done();
     }
 
-    /// Set gyro range, and update sensitivity accordingly
-    pub fn set_gyro_range(&mut self, range: GyroRange) -> Result<(), Mpu6050Error<E>> {
-        self.write_bits(
-            GYRO_CONFIG::ADDR,
-            GYRO_CONFIG::FS_SEL.bit,
-            GYRO_CONFIG::FS_SEL.length,
-            range as u8,
-        )?;
+    #[test]
+    fn save_config_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![XG_OFFS_USRH], vec![0xFF, 0xF0]),
+            I2cTransaction::write_read(0x68, vec![YG_OFFS_USRH], vec![0x00, 0x10]),
+            I2cTransaction::write_read(0x68, vec![ZG_OFFS_USRH], vec![0x00, 0x00]),
+            I2cTransaction::write_read(0x68, vec![XA_OFFS_USRH], vec![0x01, 0x01]),
+            I2cTransaction::write_read(0x68, vec![YA_OFFS_USRH], vec![0x00, 0x02]),
+            I2cTransaction::write_read(0x68, vec![ZA_OFFS_USRH], vec![0xFF, 0xFE]),
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x08]),
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0x18]),
+            I2cTransaction::write_read(0x68, vec![CONFIG::ADDR], vec![0x01]),
+            I2cTransaction::write_read(0x68, vec![SMPLRT_DIV], vec![9]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write_read(0x68, vec![INT_ENABLE::ADDR], vec![0x41]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let config = mpu.save_config().unwrap();
+        assert_eq!(
+            config,
+            ConfigSnapshot {
+                accel_range: AccelRange::G4,
+                gyro_range: GyroRange::D2000,
+                dlpf: DLPF::_184,
+                sample_rate_divider: 9,
+                gyro_offsets: [-16, 16, 0],
+                accel_offsets: [0x0100, 2, -2],
+                clock_source: CLKSEL::GXAXIS,
+                interrupt_enable: InterruptEnable {
+                    motion: true,
+                    data_ready: true,
+                    ..Default::default()
+                },
+            }
+        );
+        mpu.i2c.done();
+    }
 
-        self.gyro_sensitivity = range.sensitivity();
-        Ok(())
+    #[test]
+    fn gyro_units_consistent_test() {
+        // 262 counts = 2°/s at 250°/s, fine tune offset -131 counts on x
+        let read =
+            I2cTransaction::write_read(0x68, vec![GYRO_REGX_H], vec![0x01, 0x06, 0, 0, 0, 0]);
+        let expectations = [read.clone(), read.clone(), read];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.conv.gyro_fine_tune_offsets = Vector3d::<i32> {
+            x: -131,
+            y: 0,
+            z: 0,
+        };
+        assert_eq!(mpu.get_gyro_raw().unwrap().x, 262);
+        assert!((mpu.get_gyro_deg().unwrap().x - 1.0).abs() < 1e-6);
+        let gyro = mpu.get_gyro().unwrap();
+        assert!((gyro.x - PI_180).abs() < 1e-6);
+        assert!((gyro.as_deg_per_s().x - 1.0).abs() < 1e-6);
+        mpu.i2c.done();
     }
 
-    /// get current gyro range
-    pub fn get_gyro_range(&mut self) -> Result<GyroRange, Mpu6050Error<E>> {
-        let byte = self.read_bits(
-            GYRO_CONFIG::ADDR,
-            GYRO_CONFIG::FS_SEL.bit,
-            GYRO_CONFIG::FS_SEL.length,
-        )?;
+    #[test]
+    fn gyro_fine_tune_not_applied_to_accel_test() {
+        // 1g on z, fine tune offsets on all gyro axes
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0, 0, 0, 0, 0x40, 0x00]),
+            I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![0, 0, 0, 0, 0x40, 0x00, 0, 0, 0, 0, 0, 0, 0, 0],
+            ),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.conv.gyro_fine_tune_offsets = Vector3d::<i32> {
+            x: 100,
+            y: -100,
+            z: 50,
+        };
+        let acc = mpu.get_acc().unwrap();
+        assert_eq!((acc.x, acc.y, acc.z), (0.0, 0.0, 1.0));
+        let (acc, gyro, _) = mpu.get_motion6().unwrap();
+        assert_eq!((acc.x, acc.y, acc.z), (0.0, 0.0, 1.0));
+        assert!(gyro.x > 0.0 && gyro.y < 0.0 && gyro.z > 0.0);
+        mpu.i2c.done();
+    }
 
-        Ok(GyroRange::from(byte))
+    #[test]
+    fn fine_tune_offsets_restore_test() {
+        let read =
+            I2cTransaction::write_read(0x68, vec![GYRO_REGX_H], vec![0x00, 0x05, 0, 0, 0, 0]);
+        let expectations = [read.clone(), read];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let saved = Vector3d::<i32> { x: -5, y: 0, z: 0 };
+        mpu.set_gyro_fine_tune_offsets(saved);
+        assert_eq!(mpu.get_gyro_fine_tune_offsets(), saved);
+        assert_eq!(mpu.get_gyro().unwrap().x, 0.0);
+        mpu.set_gyro_fine_tune_offsets(Vector3d::<i32>::default());
+        assert!(mpu.get_gyro().unwrap().x > 0.0);
+        mpu.i2c.done();
     }
 
-    /// set accel range, and update sensitivy accordingly
-    pub fn set_accel_range(&mut self, range: AccelRange) -> Result<(), Mpu6050Error<E>> {
-        self.write_bits(
-            ACCEL_CONFIG::ADDR,
-            ACCEL_CONFIG::FS_SEL.bit,
-            ACCEL_CONFIG::FS_SEL.length,
-            range as u8,
-        )?;
+    #[test]
+    fn set_slave_addr_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68]),
+            I2cTransaction::write_read(0x69, vec![WHOAMI], vec![0x68]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert_eq!(mpu.who_am_i().unwrap(), 0x68);
+        mpu.set_slave_addr(0x69).unwrap();
+        assert_eq!(mpu.slave_addr(), 0x69);
+        assert_eq!(mpu.who_am_i().unwrap(), 0x68);
+        assert!(matches!(
+            mpu.set_slave_addr(0x80),
+            Err(Mpu6050Error::InvalidSlaveAddr(0x80))
+        ));
+        assert!(matches!(
+            mpu.set_slave_addr(0x03),
+            Err(Mpu6050Error::InvalidSlaveAddr(0x03))
+        ));
+        assert_eq!(mpu.slave_addr(), 0x69);
+        mpu.i2c.done();
+    }
 
-        self.acc_sensitivity = range.sensitivity();
-        Ok(())
+    #[test]
+    fn temp_disabled_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x09]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.set_temp_enabled(false).unwrap();
+        assert!(matches!(
+            mpu.get_temp(),
+            Err(Mpu6050Error::TemperatureDisabled)
+        ));
+        assert!(matches!(
+            mpu.get_temp_kelvin(),
+            Err(Mpu6050Error::TemperatureDisabled)
+        ));
+        mpu.i2c.done();
     }
 
-    /// get current accel_range
-    pub fn get_accel_range(&mut self) -> Result<AccelRange, Mpu6050Error<E>> {
-        let byte = self.read_bits(
-            ACCEL_CONFIG::ADDR,
-            ACCEL_CONFIG::FS_SEL.bit,
-            ACCEL_CONFIG::FS_SEL.length,
-        )?;
+    #[test]
+    fn shared_bus_test() {
+        // MPU6050 at 0x68 next to a second device at 0x69 on the same bus
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68]),
+            I2cTransaction::write_read(0x69, vec![0x0a], vec![0x48]),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0x40, 0x00, 0, 0, 0, 0]),
+        ];
+        let mut i2c = I2cMock::new(&expectations);
+        let bus = shared_bus::BusManagerSimple::new(i2c.clone());
+        let mut mpu = Mpu6050::new(bus.acquire_i2c());
+        let mut other = bus.acquire_i2c();
 
-        Ok(AccelRange::from(byte))
+        assert_eq!(mpu.who_am_i().unwrap(), 0x68);
+        let mut id = [0u8; 1];
+        other.write_read(0x69, &[0x0a], &mut id).unwrap();
+        assert_eq!(id[0], 0x48);
+        assert_eq!(mpu.get_acc().unwrap().x, 1.0);
+
+        i2c.done();
     }
 
-    /// reset device
-    pub fn reset_device<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<(), Mpu6050Error<E>> {
-        self.write_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::DEVICE_RESET, true)?;
-        delay.delay_ms(100u8);
-        // Note: Reset sets sleep to true! Section register map: resets PWR_MGMT to 0x40
-        Ok(())
+    #[test]
+    fn averaged_read_test() {
+        let expectations = [
+            // 16384 counts/g at 2g
+            I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![0x40, 0x00, 0x00, 0x00, 0x40, 0x00],
+            ),
+            I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![0x00, 0x00, 0x00, 0x00, 0x40, 0x00],
+            ),
+            // 131 counts per °/s at 250°/s
+            I2cTransaction::write_read(
+                0x68,
+                vec![GYRO_REGX_H],
+                vec![0x01, 0x06, 0x00, 0x00, 0x00, 0x00],
+            ),
+            I2cTransaction::write_read(
+                0x68,
+                vec![GYRO_REGX_H],
+                vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut delay = MockNoop::new();
+        let acc = mpu.get_acc_averaged(&mut delay, 2, 1).unwrap();
+        assert_eq!((acc.x, acc.y, acc.z), (0.5, 0.0, 1.0));
+        let gyro = mpu.get_gyro_averaged(&mut delay, 2, 1).unwrap();
+        assert!((gyro.x - PI_180).abs() < 1e-6);
+        assert!(matches!(
+            mpu.get_acc_averaged(&mut delay, 0, 1),
+            Err(Mpu6050Error::InvalidSampleCount)
+        ));
+        mpu.i2c.done();
     }
 
-    /// enable, disable sleep of sensor
-    pub fn set_sleep_enabled(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
-        Ok(self.write_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::SLEEP, enable)?)
+    #[test]
+    fn scale_raw_test() {
+        assert_eq!(scale_accel(8192, AccelRange::G2), 0.5);
+        assert_eq!(scale_accel(-2048, AccelRange::G16), -1.0);
+        assert_eq!(scale_gyro(131, GyroRange::D250), 1.0);
+        assert_eq!(scale_gyro(-164, GyroRange::D2000), -10.0);
+
+        // agrees with the instance methods for the same range
+        let read = vec![0x08, 0x00, 0xF8, 0x00, 0x10, 0x00];
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], read.clone()),
+            I2cTransaction::write_read(0x68, vec![GYRO_REGX_H], read),
+        ];
+        let mut mpu =
+            Mpu6050::new_with_sens(I2cMock::new(&expectations), AccelRange::G4, GyroRange::D500);
+        let acc = mpu.get_acc().unwrap();
+        assert_eq!(acc.x, scale_accel(0x0800, AccelRange::G4));
+        assert_eq!(acc.y, scale_accel(-0x0800, AccelRange::G4));
+        let gyro = mpu.get_gyro_deg().unwrap();
+        assert_eq!(gyro.z, scale_gyro(0x1000, GyroRange::D500));
+        mpu.i2c.done();
     }
 
-    /// get sleep status
-    pub fn get_sleep_enabled(&mut self) -> Result<bool, Mpu6050Error<E>> {
-        Ok(self.read_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::SLEEP)? != 0)
+    #[test]
+    fn ping_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68]),
+            // garbage on flaky wiring
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0xFF]),
+            // disconnected: NACK
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x00])
+                .with_error(embedded_hal_mock::MockError::Io(std::io::ErrorKind::Other)),
+            // MPU6500 ID, but an MPU6050 was detected by init
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![MPU6500_CHIP_ID]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert!(mpu.ping().unwrap());
+        assert!(!mpu.ping().unwrap());
+        assert!(!mpu.ping().unwrap());
+        mpu.model = Some(DeviceModel::Mpu6050);
+        assert!(!mpu.ping().unwrap());
+        mpu.i2c.done();
     }
 
-    /// enable, disable temperature measurement of sensor
-    /// TEMP_DIS actually saves "disabled status"
-    /// 1 is disabled! -> enable=true : bit=!enable
-    pub fn set_temp_enabled(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
-        Ok(self.write_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::TEMP_DIS, !enable)?)
+    #[test]
+    fn gyro_temp_compensation_test() {
+        // bias rises by 2 counts/°C on x, from 10 counts at 25°C
+        let model = GyroTempModel {
+            slope: Vector3d::<f32> {
+                x: 2.0,
+                y: 0.0,
+                z: -1.0,
+            },
+            intercept: Vector3d::<f32> {
+                x: 10.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            ref_temp: 25.0,
+        };
+        // raw temp for 35°C: (35 - 36.53) * 340 = -520
+        let temp_35 = (-520_i16).to_be_bytes();
+        let expectations = [
+            I2cTransaction::write_read(
+                0x68,
+                vec![TEMP_OUT_H],
+                vec![temp_35[0], temp_35[1], 0x00, 0x1E, 0x00, 0x00, 0xFF, 0xF6],
+            ),
+            I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![
+                    0, 0, 0, 0, 0, 0, temp_35[0], temp_35[1], 0x00, 0x1E, 0x00, 0x00, 0xFF, 0xF6,
+                ],
+            ),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.set_gyro_temp_compensation(Some(model));
+        // bias at 35°C: x = 30, z = -10, matching the readings
+        let gyro = mpu.get_gyro().unwrap();
+        assert!(gyro.x.abs() < 1e-6 && gyro.y.abs() < 1e-6 && gyro.z.abs() < 1e-6);
+        let (_, gyro, _) = mpu.get_motion6().unwrap();
+        assert!(gyro.x.abs() < 1e-6 && gyro.y.abs() < 1e-6 && gyro.z.abs() < 1e-6);
+        mpu.i2c.done();
     }
 
-    /// get temperature sensor status
-    /// TEMP_DIS actually saves "disabled status"
-    /// 1 is disabled! -> 1 == 0 : false, 0 == 0 : true
-    pub fn get_temp_enabled(&mut self) -> Result<bool, Mpu6050Error<E>> {
-        Ok(self.read_bit(PWR_MGMT_1::ADDR, PWR_MGMT_1::TEMP_DIS)? == 0)
+    #[test]
+    fn gyro_temp_compensation_temp_disabled_test() {
+        let model = GyroTempModel {
+            slope: Vector3d::<f32> {
+                x: 2.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            intercept: Vector3d::<f32> {
+                x: 10.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            ref_temp: 25.0,
+        };
+        let gyro = vec![0x00, 0x83, 0x00, 0x00, 0x00, 0x00];
+        let mut motion6 = vec![0u8; 8];
+        // stale TEMP_OUT for 35°C
+        motion6[6..8].copy_from_slice(&(-520_i16).to_be_bytes());
+        motion6.extend_from_slice(&gyro);
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x09]),
+            I2cTransaction::write_read(0x68, vec![GYRO_REGX_H], gyro),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], motion6),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.set_gyro_temp_compensation(Some(model));
+        mpu.set_temp_enabled(false).unwrap();
+        // no temperature read, no compensation: 131 counts = 1°/s at 250°/s
+        let gyro = mpu.get_gyro().unwrap();
+        assert!((gyro.as_deg_per_s().x - 1.0).abs() < 1e-4);
+        let (_, gyro, _) = mpu.get_motion6().unwrap();
+        assert!((gyro.as_deg_per_s().x - 1.0).abs() < 1e-4);
+        mpu.i2c.done();
     }
 
-    /// set accel x self test
-    pub fn set_accel_x_self_test(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
-        Ok(self.write_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::XA_ST, enable)?)
+    #[test]
+    fn gyro_offsets_vec_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![XG_OFFS_USRH], vec![0xFF, 0x38]),
+            I2cTransaction::write_read(0x68, vec![YG_OFFS_USRH], vec![0x00, 0x64]),
+            I2cTransaction::write_read(0x68, vec![ZG_OFFS_USRH], vec![0x00, 0x00]),
+            I2cTransaction::write(0x68, vec![XG_OFFS_USRH, 0xFF, 0x39]),
+            I2cTransaction::write(0x68, vec![YG_OFFS_USRH, 0x00, 0x64]),
+            I2cTransaction::write(0x68, vec![ZG_OFFS_USRH, 0x00, 0x00]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut offsets = mpu.get_gyro_offsets_vec().unwrap();
+        assert_eq!((offsets.x, offsets.y, offsets.z), (-200, 100, 0));
+        offsets.x += 1;
+        mpu.set_gyro_offsets_vec(offsets).unwrap();
+        mpu.i2c.done();
     }
 
-    /// get accel x self test
-    pub fn get_accel_x_self_test(&mut self) -> Result<bool, Mpu6050Error<E>> {
-        Ok(self.read_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::XA_ST)? != 0)
+    #[test]
+    fn acc_magnitude_test() {
+        // stationary, tilted: 0.6g on x, 0.8g on z
+        let read = I2cTransaction::write_read(
+            0x68,
+            vec![ACC_REGX_H],
+            vec![0x26, 0x66, 0x00, 0x00, 0x33, 0x33],
+        );
+        let expectations = [read.clone(), read];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert!((mpu.get_acc_magnitude().unwrap() - 1.0).abs() < 1e-3);
+        assert!((mpu.get_acc_magnitude_raw().unwrap() - 16384.0).abs() < 2.0);
+        mpu.i2c.done();
+
+        // at rest, then a -3g tap on z (8192 counts/g at 4g)
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0, 0, 0, 0, 0x20, 0x00]),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0, 0, 0, 0, 0xA0, 0x00]),
+        ];
+        let mut mpu =
+            Mpu6050::new_with_sens(I2cMock::new(&expectations), AccelRange::G4, GyroRange::D250);
+        assert!(!mpu.detect_impact(2.5).unwrap());
+        assert!(mpu.detect_impact(2.5).unwrap());
+        mpu.i2c.done();
     }
 
-    /// set accel y self test
-    pub fn set_accel_y_self_test(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
-        Ok(self.write_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::YA_ST, enable)?)
+    #[test]
+    fn product_id_test() {
+        let expectations = [I2cTransaction::write_read(
+            0x68,
+            vec![PRODUCT_ID],
+            vec![0x54],
+        )];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert_eq!(mpu.product_id().unwrap(), 0x54);
+        mpu.i2c.done();
     }
 
-    /// get accel y self test
-    pub fn get_accel_y_self_test(&mut self) -> Result<bool, Mpu6050Error<E>> {
-        Ok(self.read_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::YA_ST)? != 0)
+    #[test]
+    fn temp_calibration_test() {
+        let read = I2cTransaction::write_read(0x68, vec![TEMP_OUT_H], vec![0xFC, 0x90]);
+        let expectations = [read.clone(), read];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert_eq!(mpu.get_temp_calibration(), (TEMP_SENSITIVITY, TEMP_OFFSET));
+        assert!((mpu.get_temp().unwrap() - 33.94).abs() < 0.01);
+        // -880 / 320 + 35 = 32.25°C
+        mpu.set_temp_calibration(320.0, 35.0);
+        assert!((mpu.get_temp().unwrap() - 32.25).abs() < 1e-4);
+        mpu.i2c.done();
     }
 
-    /// set accel z self test
-    pub fn set_accel_z_self_test(&mut self, enable: bool) -> Result<(), Mpu6050Error<E>> {
-        Ok(self.write_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::ZA_ST, enable)?)
+    #[test]
+    fn fifo_i2c_mst_interrupt_test() {
+        let expectations = [
+            // motion enabled before, kept
+            I2cTransaction::write_read(0x68, vec![INT_ENABLE::ADDR], vec![0b0100_0000]),
+            I2cTransaction::write(0x68, vec![INT_ENABLE::ADDR, 0b0101_1000]),
+            I2cTransaction::write_read(0x68, vec![INT_ENABLE::ADDR], vec![0b0101_1000]),
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0b0001_1000]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let en = InterruptEnable {
+            fifo_overflow: true,
+            i2c_mst: true,
+            motion: true,
+            ..Default::default()
+        };
+        mpu.set_interrupt_enable(en).unwrap();
+        assert_eq!(mpu.get_interrupt_enable().unwrap(), en);
+        let status = mpu.get_interrupt_status().unwrap();
+        assert!(status.fifo_overflow && status.i2c_mst && !status.motion);
+        mpu.i2c.done();
     }
 
-    /// get accel z self test
-    pub fn get_accel_z_self_test(&mut self) -> Result<bool, Mpu6050Error<E>> {
-        Ok(self.read_bit(ACCEL_CONFIG::ADDR, ACCEL_CONFIG::ZA_ST)? != 0)
+    #[test]
+    fn all_offsets_test() {
+        let expectations = [
+            I2cTransaction::write_read(
+                0x68,
+                vec![XG_OFFS_USRH],
+                vec![0xFF, 0x38, 0x00, 0x64, 0x00, 0x00],
+            ),
+            // reserved bit 0 set on x
+            I2cTransaction::write_read(
+                0x68,
+                vec![XA_OFFS_USRH],
+                vec![0xF0, 0x01, 0x04, 0x00, 0x00, 0x10],
+            ),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let offsets = mpu.get_all_offsets().unwrap();
+        assert_eq!(
+            (offsets.gyro.x, offsets.gyro.y, offsets.gyro.z),
+            (-200, 100, 0)
+        );
+        assert_eq!(
+            (offsets.accel.x, offsets.accel.y, offsets.accel.z),
+            (-4096, 1024, 16)
+        );
+        mpu.i2c.done();
     }
 
-    /// Roll and pitch estimation from raw accelerometer readings
-    /// NOTE: no yaw! no magnetometer present on MPU6050
-    /// https://www.nxp.com/docs/en/application-note/AN3461.pdf equation 28, 29
-    pub fn get_acc_angles(&mut self) -> Result<Vector2d<f32>, Mpu6050Error<E>> {
-        let acc = self.get_acc()?;
+    #[test]
+    fn new_autodetect_test() {
+        let nack = embedded_hal_mock::MockError::Io(std::io::ErrorKind::Other);
+        // strapped to 0x69, nothing at 0x68
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x00]).with_error(nack.clone()),
+            I2cTransaction::write_read(0x69, vec![WHOAMI], vec![0x68]),
+        ];
+        let mut mpu = Mpu6050::new_autodetect(I2cMock::new(&expectations)).unwrap();
+        assert_eq!(mpu.slave_addr(), 0x69);
+        assert_eq!(mpu.get_device_model(), Some(DeviceModel::Mpu6050));
+        mpu.i2c.done();
 
-        Ok(Vector2d::<f32> {
-            // x: atan2f(acc.y, sqrtf(powf(acc.x, 2.) + powf(acc.z, 2.))),
-            // y: atan2f(-acc.x, sqrtf(powf(acc.y, 2.) + powf(acc.z, 2.)))
-            x: acc.y.atan2((acc.x.powf(2.) + acc.z.powf(2.)).sqrt()),
-            y: (-acc.x).atan2((acc.y.powf(2.) + acc.z.powf(2.)).sqrt()),
-        })
+        // another device at 0x68, nothing at 0x69
+        let mut i2c = I2cMock::new(&[
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x12]),
+            I2cTransaction::write_read(0x69, vec![WHOAMI], vec![0x00]).with_error(nack),
+        ]);
+        assert!(matches!(
+            Mpu6050::new_autodetect(i2c.clone()),
+            Err(Mpu6050Error::DeviceNotFound)
+        ));
+        i2c.done();
     }
 
-    /// Converts 2 bytes number in 2 compliment
-    /// TODO i16?! whats 0x8000?!
-    fn read_word_2c(&self, byte: &[u8]) -> i32 {
-        let high: i32 = byte[0] as i32;
-        let low: i32 = byte[1] as i32;
-        let mut word: i32 = (high << 8) + low;
-
-        if word >= 0x8000 {
-            word = -((65535 - word) + 1);
-        }
+    #[test]
+    fn user_ctrl_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x40]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x60]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x60]),
+            // I2C master reset keeps the FIFO running
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x60]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x60]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x40]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x40]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x42]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x40]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x60]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x60]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x61]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let ctrl = UserCtrl {
+            fifo_enabled: true,
+            i2c_master_enabled: true,
+            ..Default::default()
+        };
+        mpu.set_user_ctrl(ctrl).unwrap();
+        assert_eq!(mpu.get_user_ctrl().unwrap(), ctrl);
+        mpu.reset_i2c_master().unwrap();
+        mpu.reset_signal_path().unwrap();
+        mpu.i2c.done();
+    }
 
-        word
+    #[test]
+    fn write_word_masked_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![XA_OFFS_USRH], vec![0x12, 0x35]),
+            I2cTransaction::write(0x68, vec![XA_OFFS_USRH, 0xAB, 0xC1]),
+            I2cTransaction::write_read(0x68, vec![XA_OFFS_USRH], vec![0xF0, 0x00]),
+            I2cTransaction::write(0x68, vec![XA_OFFS_USRH, 0xF1, 0x11]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.write_word_masked(XA_OFFS_USRH, 0xABC0, 0x0001).unwrap();
+        mpu.write_word_masked(XA_OFFS_USRH, 0x0111, 0xF000).unwrap();
+        mpu.i2c.done();
     }
 
-    /// Reads rotation (gyro/acc) from specified register returning as Vector3s<i32>
-    fn read_rot_i32(&mut self, reg: u8) -> Result<Vector3d::<i32>, Mpu6050Error<E>> {
-        let mut buf: [u8; 6] = [0; 6];
-        self.read_bytes(reg, &mut buf)?;
+    #[test]
+    fn read_when_ready_test() {
+        use embedded_hal_mock::pin::{
+            Mock as PinMock, State as PinState, Transaction as PinTransaction,
+        };
 
-        Ok(Vector3d::<i32> {
-            x: self.read_word_2c(&buf[0..2]),  // x
-            y: self.read_word_2c(&buf[2..4]),  // y
-            z: self.read_word_2c(&buf[4..6]),  // z
+        let mut pin = PinMock::new(&[
+            // active high: idle, then data ready
+            PinTransaction::get(PinState::Low),
+            PinTransaction::get(PinState::High),
+            // active low: data ready, but another interrupt fired
+            PinTransaction::get(PinState::Low),
+        ]);
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0x01]),
+            I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![0x40, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            ),
+            I2cTransaction::write_read(0x68, vec![INT_PIN_CFG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![INT_PIN_CFG::ADDR, 0x80]),
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0x40]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert!(mpu.read_when_ready(&pin).unwrap().is_none());
+        let m = mpu.read_when_ready(&pin).unwrap().unwrap();
+        assert_eq!(m.acc, [1.0, 0.0, 0.0]);
+        mpu.configure_interrupt_pin(IntPinConfig {
+            active_low: true,
+            ..Default::default()
         })
+        .unwrap();
+        assert!(mpu.read_when_ready(&pin).unwrap().is_none());
+        mpu.i2c.done();
+        pin.done();
     }
 
-    /// Reads rotation (gyro/acc) from specified register
-    fn read_rot(&mut self, reg: u8) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
-        // convert i32 to Vector3d<f32>
-        let i32vec = self.read_rot_i32(reg)?;
-        Ok(Vector3d::<f32> {
-            x: i32vec.x as f32,
-            y: i32vec.y as f32,
-            z: i32vec.z as f32,
-        })
+    #[test]
+    fn accel_hpf_reset_with_motion_detection_test() {
+        let expectations = [
+            // motion interrupt enabled: rejected before touching ACCEL_CONFIG
+            I2cTransaction::write_read(0x68, vec![INT_ENABLE::ADDR], vec![0x40]),
+            I2cTransaction::write_read(0x68, vec![INT_ENABLE::ADDR], vec![0x00]),
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x01]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x00]),
+            // other modes don't check INT_ENABLE
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x01]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert!(matches!(
+            mpu.set_accel_hpf(ACCEL_HPF::_RESET),
+            Err(Mpu6050Error::InvalidConfig)
+        ));
+        mpu.set_accel_hpf(ACCEL_HPF::_RESET).unwrap();
+        mpu.set_accel_hpf(ACCEL_HPF::_5).unwrap();
+        mpu.i2c.done();
     }
 
-    /// Accelerometer readings in g
-    pub fn get_acc(&mut self) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
-        let mut acc = self.read_rot(ACC_REGX_H)?;
+    #[test]
+    fn parse_fifo_sample_test() {
+        let mut mpu = Mpu6050::new(I2cMock::new(&[]));
+        let layout = FifoLayout {
+            accel: true,
+            gyro: true,
+            ..Default::default()
+        };
+        assert_eq!(layout.sample_len(), 12);
+        // accel 1g on z, gyro 1 deg/s on x (default ranges), followed by a partial sample
+        let bytes = [
+            0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x83, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00,
+        ];
+        let sample = mpu.parse_fifo_sample(&bytes, layout).unwrap();
+        assert_eq!(
+            *sample.acc.unwrap(),
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0
+            }
+        );
+        assert!((sample.gyro.unwrap().x - PI_180).abs() < 1e-6);
+        assert_eq!(sample.temp, None);
+        let mut chunks = bytes.chunks_exact(layout.sample_len());
+        assert_eq!(chunks.by_ref().count(), 1);
+        assert_eq!(chunks.remainder().len(), 2);
+        assert!(mpu.parse_fifo_sample(chunks.remainder(), layout).is_none());
 
-        acc *= 1.0 / self.acc_sensitivity;
+        let temp_only = FifoLayout {
+            temp: true,
+            ..Default::default()
+        };
+        let sample = mpu.parse_fifo_sample(&[0x00, 0x00], temp_only).unwrap();
+        assert!((sample.temp.unwrap() - 36.53).abs() < 1e-3);
+        assert!(mpu
+            .parse_fifo_sample(&bytes, FifoLayout::default())
+            .is_none());
 
-        Ok(acc)
+        // with the temperature in the layout, the gyro temperature model applies
+        mpu.set_gyro_temp_compensation(Some(GyroTempModel {
+            slope: Vector3d::default(),
+            intercept: Vector3d {
+                x: 131.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            ref_temp: 25.0,
+        }));
+        let temp_gyro = FifoLayout {
+            temp: true,
+            gyro: true,
+            ..Default::default()
+        };
+        let sample = mpu
+            .parse_fifo_sample(&[0x00, 0x00, 0x00, 0x83, 0x00, 0x00, 0x00, 0x00], temp_gyro)
+            .unwrap();
+        assert_eq!(sample.gyro.unwrap().x, 0.0);
+        mpu.i2c.done();
     }
 
-    /// Gyro readings in rad/s
-    pub fn get_gyro(&mut self) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
-        let mut gyro = self.get_gyro_deg()?;
-
-        gyro *= PI_180;
-
-        Ok(gyro)
+    #[test]
+    fn write_bytes_test() {
+        let expectations = [
+            I2cTransaction::write(0x68, vec![FF_THR, 0x10, 0x20]),
+            I2cTransaction::write_read(0x68, vec![FF_THR], vec![0x10, 0x20]),
+            I2cTransaction::write(0x68, vec![ZRMOT_THR, 0x04, 0x02]),
+            I2cTransaction::write_read(0x68, vec![INT_ENABLE::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![INT_ENABLE::ADDR, 0x20]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.write_bytes(FF_THR, &[0x10, 0x20]).unwrap();
+        let mut buf = [0u8; 2];
+        mpu.read_bytes(FF_THR, &mut buf).unwrap();
+        assert_eq!(buf, [0x10, 0x20]);
+        mpu.setup_zero_motion_detection(0x04, 0x02).unwrap();
+        assert!(matches!(
+            mpu.write_bytes(FF_THR, &[0; MAX_BLOCK_WRITE + 1]),
+            Err(Mpu6050Error::WriteTooLong(17))
+        ));
+        mpu.i2c.done();
     }
 
-    /// Gyro readings in deg/s
-    pub fn get_gyro_deg(&mut self) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
-        let mut gyro = self.read_rot(GYRO_REGX_H)?;
-
-        gyro *= 1.0 / self.gyro_sensitivity;
-
-        Ok(gyro)
+    #[test]
+    fn is_saturated_test() {
+        let expectations = [
+            I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![
+                    0x00, 0x00, 0x7F, 0xFE, 0x40, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x10, 0x7F,
+                    0xFF,
+                ],
+            ),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0; 14]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let flags = mpu.is_saturated().unwrap();
+        assert_eq!(
+            flags,
+            SaturationFlags {
+                acc: [false; 3],
+                gyro: [true, false, true]
+            }
+        );
+        assert!(flags.any());
+        assert!(!mpu.is_saturated().unwrap().any());
+        mpu.i2c.done();
     }
 
-    /// Sensor Temp in degrees celcius
-    pub fn get_temp(&mut self) -> Result<f32, Mpu6050Error<E>> {
-        let mut buf: [u8; 2] = [0; 2];
-        self.read_bytes(TEMP_OUT_H, &mut buf)?;
-        let raw_temp = self.read_word_2c(&buf[0..2]) as f32;
+    #[test]
+    fn clock_helpers_test() {
+        let expectations = [
+            // SLEEP (bit 6) is kept
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x41]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x40]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x40]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x07]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.use_internal_clock().unwrap();
+        assert_eq!(mpu.get_clock_source().unwrap(), CLKSEL::OSCILL);
+        mpu.use_gyro_clock().unwrap();
+        assert_eq!(mpu.get_clock_source().unwrap(), CLKSEL::GXAXIS);
+        mpu.stop_clock().unwrap();
+        mpu.i2c.done();
+    }
 
-        // According to revision 4.2
-        Ok((raw_temp / TEMP_SENSITIVITY) + TEMP_OFFSET)
+    #[test]
+    fn rate_limited_reader_test() {
+        let first = vec![0x40, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let second = vec![0, 0, 0x40, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], first),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], second),
+        ];
+        // 100Hz, 10ms period
+        let mut reader = RateLimitedReader::new(Mpu6050::new(I2cMock::new(&expectations)), 100);
+        assert_eq!(reader.read(u32::MAX - 4_000).unwrap().acc, [1.0, 0.0, 0.0]);
+        // within the period across the tick wrap around: cached
+        assert_eq!(reader.read(1_000).unwrap().acc, [1.0, 0.0, 0.0]);
+        assert_eq!(reader.read(6_000).unwrap().acc, [0.0, 1.0, 0.0]);
+        assert_eq!(reader.last().acc, [0.0, 1.0, 0.0]);
+        reader.release().i2c.done();
     }
 
-    /// get gyro offsets
-    pub fn get_gyro_offsets(&mut self) -> Result<Vector3d<i32>, Mpu6050Error<E>> {
-        let mut buf: [u8; 2] = [0; 2];
-        let mut offsets: Vector3d<i32> = Vector3d::<i32>::default();
+    #[test]
+    fn motion9_test() {
+        let mut motion9 = vec![0u8; 21];
+        // accel 1g on z, mag x = 100, z = 100 counts
+        motion9[4] = 0x40;
+        motion9[14] = 100;
+        motion9[18] = 100;
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x20]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x00]),
+            I2cTransaction::write_read(0x68, vec![INT_PIN_CFG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![INT_PIN_CFG::ADDR, 0x02]),
+            I2cTransaction::write_read(AK89XX::ADDR, vec![AK89XX::WIA], vec![AK89XX::WIA_ID]),
+            I2cTransaction::write(AK89XX::ADDR, vec![AK89XX::CNTL, 0x00]),
+            I2cTransaction::write(AK89XX::ADDR, vec![AK89XX::CNTL, 0x0F]),
+            I2cTransaction::write_read(AK89XX::ADDR, vec![AK89XX::ASAX], vec![128, 128, 192]),
+            I2cTransaction::write(AK89XX::ADDR, vec![AK89XX::CNTL, 0x00]),
+            I2cTransaction::write(AK89XX::ADDR, vec![AK89XX::CNTL, 0x16]),
+            // enable_i2c_master
+            I2cTransaction::write_read(0x68, vec![INT_PIN_CFG::ADDR], vec![0x02]),
+            I2cTransaction::write(0x68, vec![INT_PIN_CFG::ADDR, 0x00]),
+            I2cTransaction::write_read(0x68, vec![I2C_MST_CTRL::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![I2C_MST_CTRL::ADDR, 0x0D]),
+            I2cTransaction::write_read(0x68, vec![USER_CTRL::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![USER_CTRL::ADDR, 0x20]),
+            // slave 0 reads HXL through ST2
+            I2cTransaction::write(0x68, vec![I2C_SLV::ADDR, 0x8C]),
+            I2cTransaction::write(0x68, vec![I2C_SLV::ADDR + 1, AK89XX::HXL]),
+            I2cTransaction::write(0x68, vec![I2C_SLV::ADDR + 2, 0x87]),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], motion9.clone()),
+        ];
+        let mut expectations = expectations.to_vec();
+        // HOFL set in ST2
+        motion9[20] = 0x08;
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![ACC_REGX_H],
+            motion9.clone(),
+        ));
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![EXT_SENS_DATA_00],
+            motion9[14..].to_vec(),
+        ));
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert!(matches!(mpu.get_mag(), Err(Mpu6050Error::InvalidConfig)));
+        mpu.setup_magnetometer(Magnetometer::AK8963, &mut MockNoop::new())
+            .unwrap();
+        let (acc, gyro, mag) = mpu.get_motion9().unwrap();
+        assert_eq!(
+            acc.as_g(),
+            Vector3d {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0
+            }
+        );
+        assert_eq!(gyro.as_rad_per_s(), Vector3d::default());
+        assert!((mag.x - 15.0).abs() < 1e-4);
+        assert_eq!(mag.y, 0.0);
+        assert!((mag.z - 18.75).abs() < 1e-4);
+        assert!(matches!(mpu.get_motion9(), Err(Mpu6050Error::MagOverflow)));
+        assert!(matches!(mpu.get_mag(), Err(Mpu6050Error::MagOverflow)));
+        mpu.i2c.done();
+    }
 
-        self.read_bytes(XG_OFFS_USRH, &mut buf)?;
-        offsets.x = self.read_word_2c(&buf[0..2]);
-        self.read_bytes(YG_OFFS_USRH, &mut buf)?;
-        offsets.y = self.read_word_2c(&buf[0..2]);
-        self.read_bytes(ZG_OFFS_USRH, &mut buf)?;
-        offsets.z = self.read_word_2c(&buf[0..2]);
+    #[test]
+    fn init_device_not_responding_test() {
+        let nack = embedded_hal_mock::MockError::Io(std::io::ErrorKind::Other);
+        let expectations = [
+            I2cTransaction::write(0x69, vec![PWR_MGMT_1::ADDR, 0x01]).with_error(nack.clone()),
+            I2cTransaction::write_read(0x69, vec![WHOAMI], vec![0x00]).with_error(nack.clone()),
+            // device answered the wake, later errors are plain bus errors
+            I2cTransaction::write(0x69, vec![PWR_MGMT_1::ADDR, 0x01]),
+            I2cTransaction::write_read(0x69, vec![WHOAMI], vec![0x00]).with_error(nack),
+        ];
+        let mut mpu = Mpu6050::new_with_addr(I2cMock::new(&expectations), 0x69);
+        let mut delay = MockNoop::new();
+        assert!(matches!(
+            mpu.init(&mut delay),
+            Err(Mpu6050Error::DeviceNotResponding(0x69))
+        ));
+        let config = Mpu6050Config {
+            skip_wake: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            mpu.init_with_config(&mut delay, config),
+            Err(Mpu6050Error::DeviceNotResponding(0x69))
+        ));
+        assert!(matches!(mpu.init(&mut delay), Err(Mpu6050Error::I2c(_))));
+        mpu.i2c.done();
+    }
 
-        Ok(offsets)
+    #[test]
+    fn auto_range_test() {
+        let mut expectations = vec![
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x18]),
+        ];
+        // gentle: peak 1g at 16g, fits 2g
+        for _ in 0..AUTO_RANGE_SAMPLES {
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![0, 0, 0xF8, 0x00, 0x08, 0x00],
+            ));
+        }
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![ACCEL_CONFIG::ADDR],
+            vec![0x18],
+        ));
+        expectations.push(I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x00]));
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![GYRO_CONFIG::ADDR],
+            vec![0x00],
+        ));
+        expectations.push(I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0x18]));
+        // violent: one spike of 1200°/s at 2000°/s, too much for 1000°/s
+        for i in 0..AUTO_RANGE_SAMPLES {
+            let x: i16 = if i == 10 { -19680 } else { 100 };
+            let [h, l] = x.to_be_bytes();
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![GYRO_REGX_H],
+                vec![h, l, 0, 0, 0, 0],
+            ));
+        }
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![GYRO_CONFIG::ADDR],
+            vec![0x18],
+        ));
+        expectations.push(I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0x18]));
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut delay = MockNoop::new();
+        assert_eq!(mpu.auto_range_accel(&mut delay).unwrap(), AccelRange::G2);
+        assert_eq!(mpu.acc_sensitivity(), AccelRange::G2.sensitivity());
+        assert_eq!(mpu.auto_range_gyro(&mut delay).unwrap(), GyroRange::D2000);
+        mpu.i2c.done();
     }
 
-    /// set gyro offsets
-    pub fn set_gyro_offsets(&mut self, x_offset: i16, y_offset: i16, z_offset: i16) -> Result<(), Mpu6050Error<E>> {
-        #[cfg(feature = "defmt")]
-        debug!("Setting gyro offsets: x: {}, y: {}, z: {}", x_offset, y_offset, z_offset);
-        self.write_word(XG_OFFS_USRH, x_offset as u16)?;
-        self.write_word(YG_OFFS_USRH, y_offset as u16)?;
-        self.write_word(ZG_OFFS_USRH, z_offset as u16)?;
-        Ok(())
+    #[test]
+    fn effective_bandwidth_test() {
+        let table = [
+            (260.0, 256.0),
+            (184.0, 188.0),
+            (94.0, 98.0),
+            (44.0, 42.0),
+            (21.0, 20.0),
+            (10.0, 10.0),
+            (5.0, 5.0),
+        ];
+        let mut expectations = vec![];
+        for cfg in 0..7u8 {
+            // EXT_SYNC_SET bits are ignored
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![CONFIG::ADDR],
+                vec![0x08 | cfg],
+            ));
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![CONFIG::ADDR],
+                vec![0x08 | cfg],
+            ));
+        }
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        for (accel, gyro) in table {
+            assert_eq!(mpu.effective_accel_bandwidth().unwrap(), accel);
+            assert_eq!(mpu.effective_gyro_bandwidth().unwrap(), gyro);
+        }
+        assert_eq!(DLPF::_44.accel_delay_ms(), 4.9);
+        assert_eq!(DLPF::_44.gyro_delay_ms(), 4.8);
+        mpu.i2c.done();
     }
 
-    /// Calibrate gyro and update offsets
-    /// To calibrate the gyro, the sensor must be stationary. The sensor should be placed on a flat, level surface. The gyro offset is the average of the readings.
-    pub fn calibrate_gyro<D: DelayMs<u8>, F: FnMut(usize)>(&mut self, delay: &mut D, mut callback: F) -> Result<(), Mpu6050Error<E>> {
-        const MAX_CALIBRATION_STEPS: usize = 20;
-        // the measurement mean is in raw units (Count)/°/s. The target is to get it as close to 0 as possible, but it is not possible to get it to 0.
-        // we will aim for getting withing 1.5 counts/°/s to 0. For a 250°/s range, this is ~0.011 °/s error
-        const TARGET_MAX_MEASUREMENT_MEAN: f32 = 1.5;
+    #[test]
+    fn write_byte_verified_test() {
+        let expectations = [
+            I2cTransaction::write(0x68, vec![SMPLRT_DIV, 0x09]),
+            I2cTransaction::write_read(0x68, vec![SMPLRT_DIV], vec![0x09]),
+            // dropped write
+            I2cTransaction::write(0x68, vec![MOT_THR, 0x14]),
+            I2cTransaction::write_read(0x68, vec![MOT_THR], vec![0x00]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.write_byte_verified(SMPLRT_DIV, 0x09).unwrap();
+        assert!(matches!(
+            mpu.write_byte_verified(MOT_THR, 0x14),
+            Err(Mpu6050Error::WriteVerifyFailed {
+                reg: MOT_THR,
+                expected: 0x14,
+                got: 0x00
+            })
+        ));
+        mpu.i2c.done();
+    }
 
-        #[cfg(feature = "defmt")]
-        info!("Calibrating gyro");
+    #[test]
+    fn temp_and_gyro_test() {
+        let expectations = [
+            // raw temp 0 (36.53°C), gyro 1°/s on y
+            I2cTransaction::write_read(
+                0x68,
+                vec![TEMP_OUT_H],
+                vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x83, 0x00, 0x00],
+            ),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let (temp, gyro) = mpu.get_temp_and_gyro().unwrap();
+        assert!((temp - 36.53).abs() < 1e-3);
+        assert_eq!(gyro.x, 0.0);
+        assert!((gyro.y - PI_180).abs() < 1e-6);
+        mpu.conv.temp_enabled = false;
+        assert!(matches!(
+            mpu.get_temp_and_gyro(),
+            Err(Mpu6050Error::TemperatureDisabled)
+        ));
+        mpu.i2c.done();
+    }
 
-        // first set current offsets to 0
-        self.set_gyro_offsets(0, 0, 0)?;
+    #[test]
+    fn reboot_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x81]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]),
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68]),
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x00]),
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0x00]),
+        ];
+        let mut mpu = Mpu6050::new_with_sens(
+            I2cMock::new(&expectations),
+            AccelRange::G8,
+            GyroRange::D1000,
+        );
+        mpu.conv.gyro_fine_tune_offsets = Vector3d { x: 3, y: 0, z: 0 };
+        mpu.reboot(&mut MockNoop::new()).unwrap();
+        assert_eq!(mpu.acc_sensitivity(), AccelRange::G2.sensitivity());
+        assert_eq!(mpu.gyro_sensitivity(), GyroRange::D250.sensitivity());
+        assert_eq!(mpu.conv.gyro_fine_tune_offsets, Vector3d::default());
+        mpu.i2c.done();
+    }
 
-        let mut offsets_found = false;
-        let mut calibration_step: usize = 0;
-        while !offsets_found && calibration_step < MAX_CALIBRATION_STEPS {
-            // get mean gyro readings
-            let mean = self.calibrate_gyro_mean_sensor(delay)?;
-
-            // calculate new offsets. To converge on the right offsets, we take the current offset
-            // and substract the the mean/4. This is repeated until the mean is close to 0 or we
-            // reach 20 iterations
-            let offsets = self.get_gyro_offsets()?;
-            let mut updated_offsets = offsets.clone();
-            if mean.x.abs() > TARGET_MAX_MEASUREMENT_MEAN {
-                updated_offsets.x = offsets.x - (mean.x.signum()*f32::max(mean.x.abs()/4.0, 1.0)) as i32;
-            }
-            if mean.y.abs() > TARGET_MAX_MEASUREMENT_MEAN {
-                updated_offsets.y = offsets.y - (mean.y.signum()*f32::max(mean.y.abs()/4.0, 1.0)) as i32;
-            }
-            if mean.z.abs() > TARGET_MAX_MEASUREMENT_MEAN {
-                updated_offsets.z = offsets.z - (mean.z.signum()*f32::max(mean.z.abs()/4.0, 1.0)) as i32;
-            }
-            self.set_gyro_offsets(
-                updated_offsets.x as i16,
-                updated_offsets.y as i16,
-                updated_offsets.z as i16,
-            )?;
+    #[test]
+    fn output_data_rate_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![CONFIG::ADDR], vec![0x03]),
+            I2cTransaction::write_read(0x68, vec![SMPLRT_DIV], vec![9]),
+            // DLPF disabled: 8 kHz base
+            I2cTransaction::write_read(0x68, vec![CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write_read(0x68, vec![SMPLRT_DIV], vec![7]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        assert_eq!(mpu.output_data_rate().unwrap(), 100.0);
+        assert_eq!(mpu.output_data_rate().unwrap(), 1000.0);
+        mpu.i2c.done();
+    }
 
-            #[cfg(feature = "defmt")]
-            info!(
-                "Calibration step: {}\n  Mean: x = {}, y  = {}, z = {}\n  Found Offsets: x = {}, y  = {}, z = {}",
-                calibration_step, mean.x, mean.y, mean.z, updated_offsets.x, updated_offsets.y, updated_offsets.z
-            );
-            // callback is any
-            callback(calibration_step);
+    #[test]
+    fn wake_check_test() {
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0; 6]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0; 6]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x41]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        // off by default
+        mpu.get_acc().unwrap();
+        mpu.set_wake_check(true);
+        assert!(matches!(mpu.get_acc(), Err(Mpu6050Error::Asleep)));
+        assert!(matches!(mpu.get_motion6(), Err(Mpu6050Error::Asleep)));
+        assert!(matches!(mpu.get_temp(), Err(Mpu6050Error::Asleep)));
+        mpu.wake_with_delay(&mut MockNoop::new(), 0).unwrap();
+        mpu.get_acc().unwrap();
+        mpu.set_sleep_enabled(true).unwrap();
+        assert!(matches!(mpu.get_gyro(), Err(Mpu6050Error::Asleep)));
+        mpu.i2c.done();
+    }
 
-            // determine if we are done
-            if mean.x.abs() < TARGET_MAX_MEASUREMENT_MEAN && mean.y.abs() < TARGET_MAX_MEASUREMENT_MEAN && mean.z.abs() < TARGET_MAX_MEASUREMENT_MEAN {
-                offsets_found = true;
-            }
-            calibration_step += 1;
-        }
+    #[test]
+    fn acc_angles_calibrated_test() {
+        // level sensor with a bias of 0.125g on x and -0.0625g on y
+        let biased = vec![0x08, 0x00, 0xFC, 0x00, 0x40, 0x00];
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], biased.clone()),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], biased),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let angles = mpu.get_acc_angles().unwrap();
+        assert!(angles.x.abs() > 0.01 && angles.y.abs() > 0.01);
+        // residual offsets as found by calibrate_accel
+        mpu.conv.acc_fine_tune_offsets = Vector3d {
+            x: -0x0800,
+            y: 0x0400,
+            z: 0,
+        };
+        let angles = mpu.get_acc_angles().unwrap();
+        assert!(angles.x.abs() < 1e-6 && angles.y.abs() < 1e-6);
+        mpu.i2c.done();
+    }
 
-        Ok(())
+    #[test]
+    fn expected_chip_id_test() {
+        let nack = embedded_hal_mock::MockError::Io(std::io::ErrorKind::Other);
+        let clone = [
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]),
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x72]),
+        ];
+        let ranges = [
+            I2cTransaction::write_read(0x68, vec![ACCEL_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![ACCEL_CONFIG::ADDR, 0x00]),
+            I2cTransaction::write_read(0x68, vec![GYRO_CONFIG::ADDR], vec![0x00]),
+            I2cTransaction::write(0x68, vec![GYRO_CONFIG::ADDR, 0x00]),
+        ];
+        let mut expectations = clone.to_vec();
+        expectations.extend_from_slice(&clone);
+        expectations.extend_from_slice(&ranges);
+        expectations.push(I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x72]));
+        expectations
+            .push(I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]).with_error(nack));
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut delay = MockNoop::new();
+        assert!(matches!(
+            mpu.init(&mut delay),
+            Err(Mpu6050Error::InvalidChipId(0x72))
+        ));
+        mpu.set_expected_chip_id(Some(0x72));
+        mpu.init(&mut delay).unwrap();
+        assert_eq!(mpu.get_device_model(), Some(DeviceModel::Mpu6050));
+        assert!(mpu.ping().unwrap());
+        assert!(matches!(
+            mpu.init(&mut delay),
+            Err(Mpu6050Error::DeviceNotResponding(0x68))
+        ));
+        mpu.i2c.done();
     }
 
-    fn calibrate_gyro_mean_sensor<D: DelayMs<u8>>(&mut self, delay: &mut D) -> Result<Vector3d<f32>, Mpu6050Error<E>> {
-        const MEASURMENT_COUNT: i32 = 1000;
-        let mut sum: Vector3d<i32> = Vector3d::<i32>::default();
+    #[test]
+    fn peek_interrupt_status_test() {
+        let expectations = [
+            // motion and data ready
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0x41]),
+            // cleared by the read before, then free fall
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0x80]),
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0x00]),
+            I2cTransaction::write_read(0x68, vec![INT_STATUS::ADDR], vec![0x00]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let status = mpu.peek_interrupt_status().unwrap();
+        assert!(status.motion && status.data_ready && !status.free_fall);
+        let status = mpu.peek_interrupt_status().unwrap();
+        assert!(status.motion && status.data_ready && status.free_fall);
+        // consuming read returns the peeked bits once
+        assert!(mpu.get_motion_detected().unwrap());
+        assert_eq!(mpu.clear_interrupts().unwrap(), InterruptStatus::default());
+        mpu.i2c.done();
+    }
 
-        // discard first 100 readings
-        for _ in 0..100 {
-            let _ = self.read_rot_i32(GYRO_REGX_H)?;
-            delay.delay_ms(2u8);
-        }
-        for _ in 0..MEASURMENT_COUNT {
-            let gyro = self.read_rot_i32(GYRO_REGX_H)?;
+    struct StepClock(core::cell::Cell<u64>);
 
-            sum += gyro;
-            delay.delay_ms(2u8);
+    impl Clock for StepClock {
+        fn now_micros(&self) -> u64 {
+            let now = self.0.get();
+            self.0.set(now + 10_000);
+            now
         }
-        let mean = Vector3d::<f32> {
-            x: sum.x as f32 / MEASURMENT_COUNT as f32,
-            y: sum.y as f32 / MEASURMENT_COUNT as f32,
-            z: sum.z as f32 / MEASURMENT_COUNT as f32,
-        };
-        Ok(mean)
     }
 
-    pub fn write_word(&mut self, reg: u8, word_value: u16) -> Result<(), Mpu6050Error<E>> {
-        let data = [reg, (word_value >> 8) as u8, (word_value & 0x00FF) as u8];
-        self.i2c.write(self.slave_addr, &data)
-           .map_err(Mpu6050Error::I2c)?;
-        // delay disabled for dev build
-        // TODO: check effects with physical unit
-        // self.delay.delay_ms(10u8);
-        Ok(())
+    #[test]
+    fn measurement_timed_test() {
+        let expectations = [
+            I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![0x40, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            ),
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0; 14]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let clock = StepClock(core::cell::Cell::new(5_000));
+        let first = mpu.get_measurement_timed(&clock).unwrap();
+        let second = mpu.get_measurement_timed(&clock).unwrap();
+        assert_eq!(first.timestamp_us, 5_000);
+        assert_eq!(first.measurement.acc, [1.0, 0.0, 0.0]);
+        assert_eq!(second.timestamp_us, 15_000);
+        assert!((second.dt_since(&first) - 0.01).abs() < 1e-6);
+        mpu.i2c.done();
     }
 
-    /// Writes byte to register
-    pub fn write_byte(&mut self, reg: u8, byte: u8) -> Result<(), Mpu6050Error<E>> {
-        self.i2c.write(self.slave_addr, &[reg, byte])
-           .map_err(Mpu6050Error::I2c)?;
-        // delay disabled for dev build
-        // TODO: check effects with physical unit
-        // self.delay.delay_ms(10u8);
-        Ok(())
+    #[test]
+    fn wake_no_delay_test() {
+        let expectations = [
+            I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x01]),
+            // other work during the settle time
+            I2cTransaction::write_read(0x68, vec![WHOAMI], vec![0x68]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.set_wake_check(true);
+        mpu.wake_no_delay().unwrap();
+        assert_eq!(mpu.who_am_i().unwrap(), 0x68);
+        assert!(mpu.awake && mpu.conv.temp_enabled);
+        mpu.i2c.done();
     }
 
-    /// Enables bit n at register address reg
-    pub fn write_bit(&mut self, reg: u8, bit_n: u8, enable: bool) -> Result<(), Mpu6050Error<E>> {
-        let mut byte: [u8; 1] = [0; 1];
-        self.read_bytes(reg, &mut byte)?;
-        bits::set_bit(&mut byte[0], bit_n, enable);
-        Ok(self.write_byte(reg, byte[0])?)
+    #[test]
+    fn detect_stall_test() {
+        let rate = [
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write_read(0x68, vec![CONFIG::ADDR], vec![0x01]),
+            I2cTransaction::write_read(0x68, vec![SMPLRT_DIV], vec![9]),
+        ];
+        let frozen = I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0x12; 14]);
+        let mut live = vec![0x12; 14];
+        live[13] = 0x13;
+        let mut expectations = rate.to_vec();
+        expectations.extend_from_slice(&[frozen.clone(), frozen.clone(), frozen.clone()]);
+        expectations.extend_from_slice(&rate);
+        expectations.extend_from_slice(&[
+            frozen,
+            I2cTransaction::write_read(0x68, vec![ACC_REGX_H], live),
+        ]);
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut delay = MockNoop::new();
+        assert!(mpu.detect_stall(&mut delay, 3).unwrap());
+        // stops at the first change
+        assert!(!mpu.detect_stall(&mut delay, 5).unwrap());
+        assert!(matches!(
+            mpu.detect_stall(&mut delay, 1),
+            Err(Mpu6050Error::InvalidSampleCount)
+        ));
+        mpu.i2c.done();
     }
 
-    /// Write bits data at reg from start_bit to start_bit+length
-    pub fn write_bits(
-        &mut self,
-        reg: u8,
-        start_bit: u8,
-        length: u8,
-        data: u8,
-    ) -> Result<(), Mpu6050Error<E>> {
-        let mut byte: [u8; 1] = [0; 1];
-        self.read_bytes(reg, &mut byte)?;
-        bits::set_bits(&mut byte[0], start_bit, length, data);
-        Ok(self.write_byte(reg, byte[0])?)
-    }
+    /// Delay adding up the requested time
+    pub(crate) struct DelayTotal(pub(crate) u32);
 
-    /// Read bit n from register
-    fn read_bit(&mut self, reg: u8, bit_n: u8) -> Result<u8, Mpu6050Error<E>> {
-        let mut byte: [u8; 1] = [0; 1];
-        self.read_bytes(reg, &mut byte)?;
-        Ok(bits::get_bit(byte[0], bit_n))
+    impl DelayMs<u8> for DelayTotal {
+        fn delay_ms(&mut self, ms: u8) {
+            self.0 += ms as u32;
+        }
     }
 
-    /// Read bits at register reg, starting with bit start_bit, until start_bit+length
-    pub fn read_bits(&mut self, reg: u8, start_bit: u8, length: u8) -> Result<u8, Mpu6050Error<E>> {
-        let mut byte: [u8; 1] = [0; 1];
-        self.read_bytes(reg, &mut byte)?;
-        Ok(bits::get_bits(byte[0], start_bit, length))
+    #[test]
+    fn detect_stall_slow_rate_test() {
+        let frozen = I2cTransaction::write_read(0x68, vec![ACC_REGX_H], vec![0x12; 14]);
+        let expectations = [
+            // 1 kHz / 256 = 3.9 Hz: 257 ms
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x01]),
+            I2cTransaction::write_read(0x68, vec![CONFIG::ADDR], vec![0x01]),
+            I2cTransaction::write_read(0x68, vec![SMPLRT_DIV], vec![255]),
+            frozen.clone(),
+            frozen.clone(),
+            // cycle mode at 1.25 Hz: 801 ms
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_1::ADDR], vec![0x29]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_2::ADDR], vec![0x07]),
+            frozen.clone(),
+            frozen,
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        let mut delay = DelayTotal(0);
+        assert!(mpu.detect_stall(&mut delay, 2).unwrap());
+        assert_eq!(delay.0, 257);
+        let mut delay = DelayTotal(0);
+        assert!(mpu.detect_stall(&mut delay, 2).unwrap());
+        assert_eq!(delay.0, 801);
+        mpu.i2c.done();
     }
 
-    /// Reads byte from register
-    pub fn read_byte(&mut self, reg: u8) -> Result<u8, Mpu6050Error<E>> {
-        let mut byte: [u8; 1] = [0; 1];
-        self.i2c.write_read(self.slave_addr, &[reg], &mut byte)
-            .map_err(Mpu6050Error::I2c)?;
-        Ok(byte[0])
+    #[test]
+    fn low_power_wake_frequency_test() {
+        // gyro standby bits are kept
+        let expectations = [
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_2::ADDR], vec![0x07]),
+            I2cTransaction::write(0x68, vec![PWR_MGMT_2::ADDR, 0x87]),
+            I2cTransaction::write_read(0x68, vec![PWR_MGMT_2::ADDR], vec![0x87]),
+        ];
+        let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+        mpu.set_low_power_wake_frequency(LpWakeCtrl::_20).unwrap();
+        assert_eq!(mpu.get_low_power_wake_frequency().unwrap(), LpWakeCtrl::_20);
+        mpu.i2c.done();
     }
 
-    /// Reads series of bytes into buf from specified reg
-    pub fn read_bytes(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Mpu6050Error<E>> {
-        self.i2c.write_read(self.slave_addr, &[reg], buf)
-            .map_err(Mpu6050Error::I2c)?;
-        Ok(())
+    #[test]
+    fn verify_compatible_models_test() {
+        for (chip_id, model) in [(0x70, DeviceModel::Mpu6500), (0x71, DeviceModel::Mpu9250)] {
+            let expectations = [I2cTransaction::write_read(
+                0x68,
+                vec![WHOAMI],
+                vec![chip_id],
+            )];
+            let mut mpu = Mpu6050::new(I2cMock::new(&expectations));
+            assert!(mpu.verify().is_ok());
+            assert_eq!(mpu.get_device_model(), Some(model));
+            mpu.i2c.done();
+        }
     }
 }
@@ -0,0 +1,140 @@
+//! I2C multiplexer support, for several MPU6050 on one bus behind a TCA9548A (or compatible) mux.
+//!
+//! All MPU6050 share one of two addresses, so more than two on a bus need a mux. Give every driver a
+//! [`MuxChannel`] around its own handle to the bus, e.g. a [`shared-bus`](https://crates.io/crates/shared-bus)
+//! proxy: each transaction selects the channel first, so the drivers can be used in any order.
+//! The driver never holds the bus between transactions, read-modify-write sequences select the
+//! channel for each step. Assumes all channel handles are used from one thread or task.
+
+use crate::Mpu6050Error;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Default address of the TCA9548A, with A0 - A2 low (0x70 - 0x77)
+pub const TCA9548A_ADDR: u8 = 0x70;
+
+/// Number of channels of the TCA9548A
+pub const TCA9548A_CHANNELS: u8 = 8;
+
+/// Bus handle behind one mux channel: selects channel on the mux before every transaction,
+/// at the cost of an extra one byte write
+pub struct MuxChannel<I> {
+    i2c: I,
+    mux_addr: u8,
+    channel: u8,
+}
+
+impl<I> MuxChannel<I> {
+    /// Selected channel
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Destroys the handle, returning the bus handle
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
+
+impl<I, E> MuxChannel<I>
+where
+    I: Write<Error = E>,
+{
+    /// Creates a handle for channel (0 - 7) of the mux at mux_addr, `InvalidMuxChannel` for other channels
+    pub fn new(i2c: I, mux_addr: u8, channel: u8) -> Result<Self, Mpu6050Error<E>> {
+        if channel >= TCA9548A_CHANNELS {
+            return Err(Mpu6050Error::InvalidMuxChannel(channel));
+        }
+        Ok(MuxChannel {
+            i2c,
+            mux_addr,
+            channel,
+        })
+    }
+
+    /// Writes the channel mask to the mux, only this channel is connected afterwards
+    fn select(&mut self) -> Result<(), E> {
+        self.i2c.write(self.mux_addr, &[1 << self.channel])
+    }
+}
+
+impl<I, E> Write for MuxChannel<I>
+where
+    I: Write<Error = E>,
+{
+    type Error = E;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), E> {
+        self.select()?;
+        self.i2c.write(address, bytes)
+    }
+}
+
+impl<I, E> WriteRead for MuxChannel<I>
+where
+    I: Write<Error = E> + WriteRead<Error = E>,
+{
+    type Error = E;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), E> {
+        self.select()?;
+        self.i2c.write_read(address, bytes, buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::device::*;
+    use crate::Mpu6050;
+    use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use std::vec;
+    use std::vec::Vec;
+
+    #[test]
+    fn mux_channel_test() {
+        // four sensors at 0x68 on channels 0 - 3, read in sequence
+        let mut expectations = vec![];
+        for channel in 0..4u8 {
+            expectations.push(I2cTransaction::write(TCA9548A_ADDR, vec![1 << channel]));
+            expectations.push(I2cTransaction::write_read(
+                0x68,
+                vec![ACC_REGX_H],
+                vec![0, channel, 0, 0, 0, 0],
+            ));
+        }
+        // read-modify-write selects the channel for both steps
+        expectations.push(I2cTransaction::write(TCA9548A_ADDR, vec![0x04]));
+        expectations.push(I2cTransaction::write_read(
+            0x68,
+            vec![PWR_MGMT_1::ADDR],
+            vec![0x01],
+        ));
+        expectations.push(I2cTransaction::write(TCA9548A_ADDR, vec![0x04]));
+        expectations.push(I2cTransaction::write(0x68, vec![PWR_MGMT_1::ADDR, 0x41]));
+        let mut i2c = I2cMock::new(&expectations);
+        let bus = shared_bus::BusManagerSimple::new(i2c.clone());
+        let mut mpus: Vec<_> = (0..4)
+            .map(|channel| {
+                Mpu6050::new(MuxChannel::new(bus.acquire_i2c(), TCA9548A_ADDR, channel).unwrap())
+            })
+            .collect();
+
+        for (channel, mpu) in mpus.iter_mut().enumerate() {
+            assert_eq!(mpu.get_acc_raw().unwrap().x, channel as i16);
+        }
+        mpus[2].set_sleep_enabled(true).unwrap();
+        assert_eq!(mpus[2].i2c_mut().channel(), 2);
+        i2c.done();
+    }
+
+    #[test]
+    fn mux_invalid_channel_test() {
+        let mut i2c = I2cMock::new(&[]);
+        assert!(matches!(
+            MuxChannel::new(i2c.clone(), TCA9548A_ADDR, TCA9548A_CHANNELS),
+            Err(Mpu6050Error::InvalidMuxChannel(8))
+        ));
+        i2c.done();
+    }
+}